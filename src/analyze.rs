@@ -0,0 +1,127 @@
+use crate::ast::{is_block_element, Node};
+use crate::render::decode_plain_text;
+
+// Returns (text, href) pairs of <a> elements with href in document order.
+pub fn extract_links(node: &Node) -> Vec<(String, String)> {
+    let mut links = Vec::new();
+    node.walk(&mut |n| {
+        if let Node::Element(element) = n {
+            if element.tag_name == "a" {
+                if let Some(href) = element.attributes.get("href") {
+                    let text = decode_plain_text(n.text_content().trim());
+                    links.push((text, href.to_string()));
+                }
+            }
+        }
+    });
+    links
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub struct TextStats {
+    pub words: usize,      // runs of non-whitespace characters
+    pub characters: usize, // non-whitespace characters
+    pub links: usize,      // <a> elements with href
+}
+
+// Counts over the text which a reader sees, i.e. not in <head>, <script>,
+// <style> or <template>, with entities decoded.
+pub fn text_stats(node: &Node) -> TextStats {
+    fn collect(node: &Node, text: &mut String, stats: &mut TextStats) {
+        match node {
+            Node::Element(element) => {
+                if matches!(
+                    element.tag_name.as_str(),
+                    "head" | "script" | "style" | "template"
+                ) {
+                    return;
+                }
+                if element.tag_name == "a" && element.attributes.contains_key("href") {
+                    stats.links += 1;
+                }
+                // Words don't continue across blocks, table cells and line
+                // breaks.
+                let separated = is_block_element(&element.tag_name)
+                    || matches!(element.tag_name.as_str(), "br" | "td" | "th" | "tr");
+                if separated {
+                    text.push(' ');
+                }
+                for child in &element.children {
+                    collect(child, text, stats);
+                }
+                if separated {
+                    text.push(' ');
+                }
+            }
+            Node::Text(content) => text.push_str(&decode_plain_text(content)),
+        }
+    }
+
+    let mut text = String::new();
+    let mut stats = TextStats::default();
+    collect(node, &mut text, &mut stats);
+    stats.words = text.split_whitespace().count();
+    stats.characters = text.chars().filter(|c| !c.is_whitespace()).count();
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_html;
+
+    #[test]
+    fn test_extract_links() {
+        let source = "<html><head></head><body><p>See <a href=\"https://example.com\">the <em>example</em></a>.</p><a name=\"top\">top</a><ul><li><a href=\"/foo\">foo &#38; bar</a></li><li><a href=\"/bar\">a&nbsp;&copy;</a></li></ul><a href=\"/empty\"></a></body></html>";
+        let node = match parse_html(source) {
+            Ok(node) => node,
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        };
+
+        assert_eq!(
+            extract_links(&node),
+            vec![
+                ("the example".to_string(), "https://example.com".to_string()),
+                ("foo & bar".to_string(), "/foo".to_string()),
+                ("a\u{A0}\u{A9}".to_string(), "/bar".to_string()),
+                ("".to_string(), "/empty".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_text_stats() {
+        let source = "<html><head><title>Title</title></head><body><h1>Hello world</h1><p>See <a href=\"/a\">caf&#233;</a>, or<br>not.</p><a name=\"top\"></a><script>var x = 1;</script></body></html>";
+        let node = match parse_html(source) {
+            Ok(node) => node,
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        };
+
+        assert_eq!(
+            text_stats(&node),
+            TextStats {
+                words: 6,
+                characters: 24,
+                links: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_text_stats_with_cells_and_named_entities() {
+        let source = "<html><head></head><body><table><tr><td>alpha</td><td>beta</td></tr><tr><td>a&nbsp;b &copy;</td></tr></table></body></html>";
+        let node = match parse_html(source) {
+            Ok(node) => node,
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        };
+
+        assert_eq!(
+            text_stats(&node),
+            TextStats {
+                words: 5,
+                characters: 12,
+                links: 0,
+            }
+        );
+    }
+}
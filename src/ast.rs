@@ -1,10 +1,10 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
 
 #[derive(Debug, PartialEq)]
-pub enum Token {
+pub enum Token<'a> {
     Sgml,
     Tag(Tag),
-    Text(String),
+    Text(Cow<'a, str>),
 }
 
 #[derive(Debug, PartialEq)]
@@ -14,7 +14,68 @@ pub enum TagKind {
     Void,
 }
 
-pub type AttributeMap = HashMap<String, String>;
+// Attributes in source order. Names are case-insensitive and kept lowercased
+// like parsed ones. Setting an existing name replaces its value in place.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct AttributeMap {
+    entries: Vec<(String, String)>,
+}
+
+impl AttributeMap {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&String> {
+        self.entries
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    pub fn insert(&mut self, mut name: String, value: String) -> Option<String> {
+        name.make_ascii_lowercase();
+        match self.entries.iter_mut().find(|(n, _)| *n == name) {
+            Some(entry) => Some(std::mem::replace(&mut entry.1, value)),
+            None => {
+                self.entries.push((name, value));
+                None
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.entries.iter().map(|(name, _)| name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.entries.iter().map(|(name, value)| (name, value))
+    }
+}
+
+impl<const N: usize> From<[(String, String); N]> for AttributeMap {
+    fn from(entries: [(String, String); N]) -> Self {
+        let mut map = Self::new();
+        for (name, value) in entries {
+            map.insert(name, value);
+        }
+        map
+    }
+}
 
 #[derive(Debug, PartialEq)]
 pub struct Tag {
@@ -24,28 +85,74 @@ pub struct Tag {
 }
 
 #[derive(Debug, PartialEq, Clone)]
-pub enum Node {
-    Element(Element),
-    Text(String),
+pub enum Node<'a> {
+    Element(Element<'a>),
+    Text(Cow<'a, str>),
 }
 
-impl Node {
+impl<'a> Node<'a> {
     pub fn is_list_element(&self) -> bool {
         match self {
             Self::Element(element) => element.is_list_element(),
             Self::Text(_) => false,
         }
     }
+
+    // Concatenates the text of this node and its descendants as written.
+    pub fn text_content(&self) -> String {
+        let mut result = String::new();
+        self.walk(&mut |node| {
+            if let Self::Text(content) = node {
+                result.push_str(content);
+            }
+        });
+        result
+    }
+
+    // Copies borrowed text so that the node outlives the source.
+    pub fn into_owned(self) -> Node<'static> {
+        match self {
+            Self::Element(element) => Node::Element(element.into_owned()),
+            Self::Text(content) => Node::Text(Cow::Owned(content.into_owned())),
+        }
+    }
+
+    // Visits this node and its descendants in depth-first order.
+    pub fn walk<F: FnMut(&Node<'a>)>(&self, f: &mut F) {
+        f(self);
+        if let Self::Element(element) = self {
+            for child in &element.children {
+                child.walk(f);
+            }
+        }
+    }
+
+    pub fn walk_mut<F: FnMut(&mut Node<'a>)>(&mut self, f: &mut F) {
+        f(self);
+        if let Self::Element(element) = self {
+            for child in element.children.iter_mut() {
+                child.walk_mut(f);
+            }
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
-pub struct Element {
+pub struct Element<'a> {
     pub tag_name: String,
-    pub children: Vec<Node>,
+    pub children: Vec<Node<'a>>,
     pub attributes: AttributeMap,
 }
 
-impl Element {
+impl<'a> Element<'a> {
+    pub fn into_owned(self) -> Element<'static> {
+        Element {
+            tag_name: self.tag_name,
+            children: self.children.into_iter().map(Node::into_owned).collect(),
+            attributes: self.attributes,
+        }
+    }
+
     pub fn new(tag_name: &str, attributes: &AttributeMap) -> Self {
         Self {
             tag_name: tag_name.to_string(),
@@ -57,7 +164,7 @@ impl Element {
     pub fn new_with_children(
         tag_name: &str,
         attributes: &AttributeMap,
-        children: Vec<Node>,
+        children: Vec<Node<'a>>,
     ) -> Self {
         Self {
             tag_name: tag_name.to_string(),
@@ -66,7 +173,7 @@ impl Element {
         }
     }
 
-    fn css_classes(&self) -> Vec<String> {
+    pub(crate) fn css_classes(&self) -> Vec<String> {
         match self.attributes.get("class") {
             Some(value) => value
                 .as_str()
@@ -77,26 +184,55 @@ impl Element {
         }
     }
 
-    pub fn list_depth(&self) -> usize {
-        let found = self
-            .css_classes()
-            .iter()
-            .filter(|class| class.contains('-'))
-            .map(|class| {
-                let n = class.split('-').last().unwrap();
-                n.parse::<usize>()
-            })
-            .filter(|n| n.is_ok())
-            .last();
-        if let Some(ok) = found {
-            ok.unwrap()
-        } else {
-            0
+    // Declarations in the style attribute as (property, value) pairs.
+    pub fn style_declarations(&self) -> Vec<(String, String)> {
+        match self.attributes.get("style") {
+            Some(value) => value
+                .split(';')
+                .filter_map(|declaration| declaration.split_once(':'))
+                .map(|(property, value)| {
+                    (
+                        property.trim().to_ascii_lowercase(),
+                        value.trim().to_ascii_lowercase(),
+                    )
+                })
+                .collect(),
+            None => Vec::new(),
         }
     }
 
+    // The align attribute takes precedence over text-align in the style
+    // attribute.
+    pub(crate) fn text_alignment(&self) -> Option<String> {
+        let align = match self.attributes.get("align") {
+            Some(align) => align.to_ascii_lowercase(),
+            None => self
+                .style_declarations()
+                .into_iter()
+                .rev()
+                .find(|(property, _)| property == "text-align")
+                .map(|(_, value)| value)?,
+        };
+        match align.as_str() {
+            "left" | "center" | "right" | "justify" => Some(align),
+            _ => None,
+        }
+    }
+
+    // The depth of a flat list like Google Docs emits, taken from the number
+    // at the end of a class with the prefix, like `lst-kix_abc-2` for `lst-`.
+    pub fn list_depth(&self, class_prefix: &str) -> usize {
+        self.css_classes()
+            .iter()
+            .filter(|class| class.starts_with(class_prefix))
+            .filter_map(|class| class.rsplit_once('-'))
+            .filter_map(|(_, n)| n.parse::<usize>().ok())
+            .next_back()
+            .unwrap_or(0)
+    }
+
     pub fn is_list_element(&self) -> bool {
-        self.tag_name == "ul" || self.tag_name == "ol"
+        matches!(self.tag_name.as_str(), "ul" | "ol" | "menu")
     }
 }
 
@@ -147,6 +283,7 @@ pub fn is_block_element(tag_name: &str) -> bool {
             | "hr"
             | "li"
             | "main"
+            | "menu"
             | "nav"
             | "noscript"
             | "ol"
@@ -154,9 +291,11 @@ pub fn is_block_element(tag_name: &str) -> bool {
             | "pre"
             | "section"
             | "table"
+            | "template"
             | "tfoot"
             | "ul"
             | "video"
+            | "html2md:html-table"
             | "html2md:successive-lists-wrapper"
     )
 }
@@ -165,6 +304,82 @@ pub fn is_block_element(tag_name: &str) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_node_walk() {
+        let mut node = Node::Element(Element::new_with_children(
+            "p",
+            &AttributeMap::new(),
+            vec![
+                Node::Text("hello".into()),
+                Node::Element(Element::new_with_children(
+                    "em",
+                    &AttributeMap::new(),
+                    vec![Node::Text("world".into())],
+                )),
+            ],
+        ));
+
+        let mut visited = Vec::new();
+        node.walk(&mut |n| match n {
+            Node::Element(element) => visited.push(element.tag_name.clone()),
+            Node::Text(content) => visited.push(content.to_string()),
+        });
+        assert_eq!(visited, vec!["p", "hello", "em", "world"]);
+
+        node.walk_mut(&mut |n| {
+            if let Node::Text(content) = n {
+                *content = content.to_uppercase().into();
+            }
+        });
+        let mut texts = Vec::new();
+        node.walk(&mut |n| {
+            if let Node::Text(content) = n {
+                texts.push(content.clone());
+            }
+        });
+        assert_eq!(texts, vec!["HELLO", "WORLD"]);
+    }
+
+    #[test]
+    fn test_attribute_map_keeps_order() {
+        let mut attributes = AttributeMap::new();
+        attributes.insert("src".to_string(), "a.png".to_string());
+        attributes.insert("alt".to_string(), "a".to_string());
+        assert_eq!(
+            attributes.insert("src".to_string(), "b.png".to_string()),
+            Some("a.png".to_string())
+        );
+
+        assert_eq!(attributes.len(), 2);
+        assert_eq!(attributes.get("src"), Some(&"b.png".to_string()));
+        assert_eq!(
+            attributes.keys().collect::<Vec<&String>>(),
+            vec!["src", "alt"]
+        );
+
+        attributes.insert("ALT".to_string(), "b".to_string());
+        assert_eq!(attributes.len(), 2);
+        assert_eq!(attributes.get("Alt"), Some(&"b".to_string()));
+    }
+
+    #[test]
+    fn test_element_style_declarations() {
+        let element = Element::new(
+            "span",
+            &AttributeMap::from([(
+                "style".to_string(),
+                "font-weight: bold;FONT-STYLE:italic; ;color".to_string(),
+            )]),
+        );
+        assert_eq!(
+            element.style_declarations(),
+            vec![
+                ("font-weight".to_string(), "bold".to_string()),
+                ("font-style".to_string(), "italic".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn test_element_list_depth() {
         {
@@ -172,42 +387,75 @@ mod tests {
                 "ul",
                 &AttributeMap::from([("class".to_string(), "foo-2".to_string())]),
             );
-            assert_eq!(element.list_depth(), 2)
+            assert_eq!(element.list_depth("foo-"), 2)
         }
         {
             let element = Element::new(
                 "ul",
                 &AttributeMap::from([("class".to_string(), "bar foo-2".to_string())]),
             );
-            assert_eq!(element.list_depth(), 2)
+            assert_eq!(element.list_depth("foo-"), 2)
         }
         {
             let element = Element::new(
                 "ul",
                 &AttributeMap::from([("class".to_string(), "foo-2 bar".to_string())]),
             );
-            assert_eq!(element.list_depth(), 2)
+            assert_eq!(element.list_depth("foo-"), 2)
         }
         {
             let element = Element::new(
                 "ul",
                 &AttributeMap::from([("class".to_string(), "foo-2 bar-3 buz-4".to_string())]),
             );
-            assert_eq!(element.list_depth(), 4)
+            assert_eq!(element.list_depth("foo-"), 2)
+        }
+        {
+            let element = Element::new(
+                "ul",
+                &AttributeMap::from([("class".to_string(), "foo-1 foo-bar-3".to_string())]),
+            );
+            assert_eq!(element.list_depth("foo-"), 3)
+        }
+        {
+            let element = Element::new(
+                "ul",
+                &AttributeMap::from([("class".to_string(), "col-md-6".to_string())]),
+            );
+            assert_eq!(element.list_depth("lst-"), 0)
         }
         {
             let element = Element::new(
                 "ul",
                 &AttributeMap::from([("class".to_string(), "".to_string())]),
             );
-            assert_eq!(element.list_depth(), 0)
+            assert_eq!(element.list_depth("foo-"), 0)
         }
         {
             let element = Element::new(
                 "ul",
                 &AttributeMap::from([("class".to_string(), "foo-bar".to_string())]),
             );
-            assert_eq!(element.list_depth(), 0)
+            assert_eq!(element.list_depth("foo-"), 0)
         }
     }
+
+    #[test]
+    fn test_node_into_owned() {
+        let source = String::from("hello");
+        let node = Node::Element(Element::new_with_children(
+            "p",
+            &AttributeMap::new(),
+            vec![Node::Text(Cow::Borrowed(&source))],
+        ));
+        let owned = node.clone().into_owned();
+        drop(source);
+
+        let Node::Element(element) = owned else {
+            panic!("Expected element");
+        };
+        assert!(
+            matches!(&element.children[0], Node::Text(Cow::Owned(content)) if content == "hello")
+        );
+    }
 }
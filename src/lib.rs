@@ -0,0 +1,2382 @@
+use std::error::Error;
+
+pub mod analyze;
+pub mod ast;
+pub mod options;
+pub mod parse;
+pub mod plain;
+pub mod render;
+pub mod restruct;
+pub mod select;
+pub mod serialize;
+pub mod tokenize;
+pub mod url;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub mod wasm;
+
+pub use analyze::{extract_links, text_stats, TextStats};
+pub use ast::{AttributeMap, Element, Node};
+pub use options::Options;
+pub use plain::render_plain;
+pub use restruct::restruct;
+pub use serialize::serialize_html;
+
+pub fn convert(source: &str) -> Result<String, Box<dyn Error>> {
+    convert_with_options(source, &Options::default())
+}
+
+pub fn convert_with_options(source: &str, options: &Options) -> Result<String, Box<dyn Error>> {
+    let (markdown, _) = convert_with_warnings(source, options)?;
+    Ok(markdown)
+}
+
+/// Converts like `convert_with_options` and also returns the warnings
+/// reported while tokenizing and rendering, e.g. unsupported elements.
+pub fn convert_with_warnings(
+    source: &str,
+    options: &Options,
+) -> Result<(String, Vec<String>), Box<dyn Error>> {
+    if let Some(limit) = options.max_input_bytes {
+        if source.len() > limit {
+            return Err(Box::new(tokenize::TokenizeError::TooLarge(
+                limit,
+                source.len(),
+            )));
+        }
+    }
+    let mut tokenizer = tokenize::Tokenizer::new(source);
+    let tokens = tokenizer.tokenize()?;
+    let max_depth = options.max_depth.unwrap_or(options::DEFAULT_MAX_DEPTH);
+    let original_node = parse::Parser::with_max_depth(&tokens, max_depth).parse()?;
+    let node = restruct(&original_node, options);
+    let mut renderer = render::Renderer::new(&node, options);
+    let markdown = renderer.render()?;
+
+    let mut warnings = tokenizer.warnings().to_vec();
+    if tokenizer.malformed_count() > 0 {
+        warnings.push(format!(
+            "{} malformed tokens are ignored.",
+            tokenizer.malformed_count()
+        ));
+    }
+    warnings.extend_from_slice(renderer.warnings());
+    Ok((markdown, warnings))
+}
+
+/// Converts only the first element matching the selector, which is one of
+/// `#id`, `.class` or `tag`. It is an error when no element matches.
+pub fn convert_selector(source: &str, selector: &str) -> Result<String, Box<dyn Error>> {
+    let options = Options::default();
+    let parsed = select::Selector::parse(selector)?;
+    let node = parse_html(source)?;
+    let element = match select::select_first(&node, &parsed) {
+        Some(element) => element,
+        None => {
+            return Err(Box::new(select::SelectError::NotFound(
+                selector.to_string(),
+            )))
+        }
+    };
+    let node = restruct(&Node::Element(element.clone()), &options);
+    Ok(render_node(&node, &options)?)
+}
+
+/// Converts into the readable text without Markdown syntax, e.g. a link into
+/// its text, keeping paragraphs and lines.
+pub fn convert_to_plain_text(source: &str) -> Result<String, Box<dyn Error>> {
+    let node = parse_html(source)?;
+    let node = restruct(&node, &Options::default());
+    Ok(render_plain(&node))
+}
+
+/// Parses HTML into a tree whose root is the first element in the source,
+/// usually `<html>`. Tag and attribute names are lowercased and text is kept
+/// as written, i.e. entities are not decoded yet.
+///
+/// The tree is as written in the source and has to be passed to `restruct`
+/// before `render_node`.
+pub fn parse_html(source: &str) -> Result<Node<'_>, Box<dyn Error>> {
+    let tokens = tokenize::Tokenizer::new(source).tokenize()?;
+    let node = parse::Parser::new(&tokens).parse()?;
+    Ok(node)
+}
+
+/// Renders a tree returned by `restruct` into Markdown.
+///
+/// `restruct` normalizes tables into `<thead>` and `<tbody>` with at least one
+/// row and wraps successive lists, which the renderer relies on. A tree
+/// modified after `restruct` must keep these structures. An `<html>` element
+/// must have a `<body>` child.
+pub fn render_node(node: &Node, options: &Options) -> Result<String, render::RenderError> {
+    render::Renderer::new(node, options).render()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::*;
+
+    #[test]
+    fn test_convert_only_body() {
+        let source = "<body>hello</body>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "hello\n"),
+            Err(e) => panic!("Expected Ok(\"Hello!\") but got Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_trailing_newline() {
+        for (source, trailing_newline, expected) in [
+            ("<body>hello<br></body>", TrailingNewline::None, "hello"),
+            ("<body>hello</body>", TrailingNewline::Single, "hello\n"),
+            ("<body>hello<br></body>", TrailingNewline::Single, "hello\n"),
+            ("<body>hello</body>", TrailingNewline::Preserve, "hello"),
+            (
+                "<body>hello<br></body>",
+                TrailingNewline::Preserve,
+                "hello\n",
+            ),
+        ] {
+            let options = Options {
+                trailing_newline,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_text() {
+        let source = "<!DOCTYPE html><html><head></head><body>Hello!</body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "Hello!\n"),
+            Err(e) => panic!("Expected Ok(\"Hello!\") but got Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_blockquote() {
+        let source = "<!DOCTYPE html><html><head></head><body><blockquote>hello<br/>world</blockquote></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "> hello\n> world\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_blockquote_with_p() {
+        let source = "<!DOCTYPE html><html><head></head><body><blockquote><p>hello</p><p>world</p></blockquote></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "> hello\n>\n> world\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_address() {
+        let source = "<html><head></head><body><p>hello</p><address>John Doe<br>Tokyo</address></body></html>";
+        {
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "hello\n\nJohn Doe\nTokyo\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let options = Options {
+                address: Address::Italic,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, "hello\n\n_John Doe_\n_Tokyo_\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let options = Options {
+                address: Address::Blockquote,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, "hello\n\n> John Doe\n> Tokyo\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_no_trailing_spaces() {
+        let source = "<html><head></head><body><blockquote><p>hello </p><p>world</p></blockquote><ul><li><p>foo</p><p>bar</p></li></ul></body></html>";
+        match convert(source) {
+            Ok(result) => {
+                assert_eq!(result, "> hello\n>\n> world\n\n- foo\n\n  bar\n");
+                for line in result.lines() {
+                    assert!(!line.ends_with(' '), "{:?} ends with a space", line);
+                }
+            }
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_trailing_spaces_in_code_block() {
+        let source = "<pre>a  \nb   \n</pre>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "```\na  \nb   \n```\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_blank_lines_in_longer_fence() {
+        let source = "<pre>a\n```\n\n\nb</pre>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "````\na\n```\n\n\nb\n````\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_br() {
+        let source = "<!DOCTYPE html><html><head></head><body>hello<br/>world</body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "hello\nworld\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_br_at_the_end_of_block() {
+        {
+            let source = "<html><head></head><body><blockquote><p>hello<br></p><p>world</p></blockquote></body></html>";
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "> hello\n>\n> world\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let source = "<html><head></head><body><table><tr><th>hello<br></th></tr><tr><td>world<br></td></tr></table></body></html>";
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "| hello |\n|---|\n| world |\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_br_in_context() {
+        for (source, expected) in [
+            ("<p>a<br>b</p>", "a\nb\n"),
+            ("<h2>a<br>b</h2>", "## a b\n"),
+            (
+                "<table><tr><th>h</th></tr><tr><td><strong>a<br>b</strong></td></tr></table>",
+                "| h |\n|---|\n| **a<br>b** |\n",
+            ),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert(&source) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_successive_br() {
+        let options = Options {
+            coalesce_br: true,
+            ..Default::default()
+        };
+        for source in [
+            "<html><head></head><body><blockquote>hello<br><br>world</blockquote></body></html>",
+            "<html><head></head><body><blockquote>hello<br><br><br>world</blockquote></body></html>",
+        ] {
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, "> hello\n>\n> world\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let source = "<html><head></head><body><blockquote>hello<br><br><br>world</blockquote></body></html>";
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "> hello\n>\n>\n> world\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_code() {
+        let source =
+            "<!DOCTYPE html><html><head></head><body>This is <code>hello</code>.</body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "This is `hello`.\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_code_including_backticks() {
+        {
+            let source = "<html><head></head><body><code>a`b</code></body></html>";
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "``a`b``\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let source = "<html><head></head><body><code>a``b`c</code></body></html>";
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "```a``b`c```\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let source = "<html><head></head><body><code>`a``</code></body></html>";
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "``` `a`` ```\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_del() {
+        let source =
+            "<!DOCTYPE html><html><head></head><body>This is <del>hello</del>.</body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "This is ~hello~.\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ins() {
+        let source =
+            "<!DOCTYPE html><html><head></head><body>This is <ins>hello</ins>.</body></html>";
+        {
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "This is <ins>hello</ins>.\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let options = Options {
+                ins: Ins::PlusPlus,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, "This is ++hello++.\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_bdo() {
+        for (source, expected) in [
+            (
+                "<html><head></head><body>It says <bdo dir=\"rtl\">hello</bdo>.</body></html>",
+                "It says <bdo dir=\"rtl\">hello</bdo>.\n",
+            ),
+            (
+                "<html><head></head><body>It says <bdo>hello</bdo>.</body></html>",
+                "It says hello.\n",
+            ),
+        ] {
+            match convert(source) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_data() {
+        let source =
+            "<html><head></head><body>Answer: <data value=\"42\">forty-two</data></body></html>";
+        {
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "Answer: forty-two\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let options = Options {
+                data_html: true,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, "Answer: <data value=\"42\">forty-two</data>\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+            let source =
+                "<html><head></head><body><data value=\"SKU-ABC\">Blue Widget</data></body></html>";
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, "<data value=\"SKU-ABC\">Blue Widget</data>\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_div() {
+        let source =
+            "<!DOCTYPE html><html><head></head><body><div><p>hello</p><p>world</p></div></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "hello\n\nworld\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_nested_div_with_empty_div() {
+        let source = "<html><head></head><body><div><div><p>hello</p></div><div></div><div><div></div></div><p>world</p></div></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "hello\n\nworld\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_dl() {
+        let source = "<html><head></head><body><dl><dt>foo</dt><dd>hello</dd><dt>bar</dt><dd>world</dd></dl></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "foo\n: hello\nbar\n: world\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_dl_in_li() {
+        let source = "<html><head></head><body><ul><li><dl><dt>foo</dt><dd>hello<br>world</dd></dl></li></ul></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "- foo\n  : hello\n    world\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_dl_in_td() {
+        let source = "<html><head></head><body><table><tr><th>1</th></tr><tr><td><dl><dt>foo</dt><dd>hello</dd></dl></td></tr></table></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "| 1 |\n|---|\n| foo<br>: hello |\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_em() {
+        let source =
+            "<!DOCTYPE html><html><head></head><body>This is <em>hello</em>.</body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "This is _hello_.\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_figure() {
+        let source = "<html><head></head><body><figure><img src=\"x.png\"><figcaption>A cat</figcaption></figure></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "<img src=\"x.png\">\n\n_A cat_\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_heading() {
+        let source = "<!DOCTYPE html><html><head></head><body><h1>H1</h1><h2>H2</h2><h3>H3</h3><h4>H4</h4><h5>H5</h5><h6>H6</h6></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(
+                result,
+                "# H1\n\n## H2\n\n### H3\n\n#### H4\n\n##### H5\n\n###### H6\n"
+            ),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_hr() {
+        let source =
+            "<!DOCTYPE html><html><head></head><body><p>para1</p><hr/><p>para2</p></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "para1\n\n---\n\npara2\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_hr_thematic_break() {
+        let source = "<html><head></head><body><p>para1</p><hr/><p>para2</p></body></html>";
+        for rule in ["***", "___", "- - -"] {
+            let options = Options {
+                thematic_break: Some(rule.to_string()),
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, format!("para1\n\n{}\n\npara2\n", rule)),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+
+        for rule in ["", "--", "-*-", "===", "--- x"] {
+            let options = Options {
+                thematic_break: Some(rule.to_string()),
+                ..Default::default()
+            };
+            if let Ok(result) = convert_with_options(source, &options) {
+                panic!("Expected Err for {:?} but got Ok({:?})", rule, result);
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_paragraph() {
+        let source =
+            "<!DOCTYPE html><html><head></head><body><p>para1</p><p>para2</p></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "para1\n\npara2\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ruby() {
+        let source =
+                "<!DOCTYPE html><html><head></head><body><ruby>hello<rt>world</rt></ruby></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "hello\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ruby_with_rp_and_rt() {
+        let source =
+                "<!DOCTYPE html><html><head></head><body><ruby>hello<rp>(</rp><rt>world</rt><rp>)</rp></ruby></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "hello\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ruby_with_options() {
+        for (source, ruby, expected) in [
+            (
+                "<ruby>漢<rt>かん</rt>字<rt>じ</rt></ruby>",
+                Ruby::Parentheses,
+                "漢(かん)字(じ)\n",
+            ),
+            (
+                "<ruby>hello<rp>(</rp><rt>world</rt><rp>)</rp></ruby>",
+                Ruby::Parentheses,
+                "hello(world)\n",
+            ),
+            (
+                "<ruby>hello<rp>(</rp><rt>world</rt><rp>)</rp></ruby>",
+                Ruby::Html,
+                "<ruby>hello<rp>(</rp><rt>world</rt><rp>)</rp></ruby>\n",
+            ),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            let options = Options {
+                ruby,
+                ..Default::default()
+            };
+            match convert_with_options(&source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_strong() {
+        let source =
+                "<!DOCTYPE html><html><head></head><body>This is <strong>strong</strong>.</body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "This is **strong**.\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_time() {
+        let source =
+            "<html><head></head><body><time datetime=\"2020-01-01\">Jan 1</time></body></html>";
+        {
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "Jan 1\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let options = Options {
+                time_datetime: true,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, "Jan 1 (2020-01-01)\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_wbr() {
+        let source = "<html><head></head><body>hello<wbr>world</body></html>";
+        {
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "helloworld\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let options = Options {
+                wbr: Wbr::ZeroWidthSpace,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, "hello\u{200B}world\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let options = Options {
+                wbr: Wbr::SoftHyphen,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, "hello\u{00AD}world\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_spaces_inside_inline_elements() {
+        {
+            let source = "<html><head></head><body>a <strong>b </strong>c</body></html>";
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "a **b** c\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let source = "<html><head></head><body>a<em>b  </em>c</body></html>";
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "a_b_  c\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        for (source, expected) in [
+            ("<p>a<strong> b </strong>c</p>", "a **b** c\n"),
+            (
+                "<p>This is <em>hello</em> world</p>",
+                "This is _hello_ world\n",
+            ),
+            ("<p><em>a</em> <strong>b</strong></p>", "_a_ **b**\n"),
+            ("<p>a<a href=\"u\"> link </a>b</p>", "a [link](u) b\n"),
+        ] {
+            match convert(source) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_indented_source() {
+        for (source, expected) in [
+            ("<ul>\n  <li>a</li>\n  <li>b</li>\n</ul>", "- a\n- b\n"),
+            (
+                "<table>\n  <tr>\n    <th>x</th>\n  </tr>\n  <tr>\n    <td>y</td>\n  </tr>\n</table>",
+                "| x |\n|---|\n| y |\n",
+            ),
+            ("<div>\n  <p>a</p>\n  <p>b</p>\n</div>", "a\n\nb\n"),
+            ("<p>a <br> b</p>", "a\nb\n"),
+            ("<pre>\nfoo\n  bar\n</pre>", "```\nfoo\n  bar\n```\n"),
+        ] {
+            match convert(source) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_empty_inline_elements() {
+        let source = "<html><head></head><body>a<strong></strong><em></em><code></code><del></del><ins></ins>b</body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "ab\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_nested_strong() {
+        let source = "<html><head></head><body><strong>a<strong>b</strong>c</strong></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "**abc**\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_nested_em() {
+        let source = "<html><head></head><body><em><span><em>hello</em></span></em></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "_hello_\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_styled_span_in_same_emphasis() {
+        for (source, expected) in [
+            (
+                "<strong><span style=\"font-weight:bold\">x</span></strong>",
+                "**x**\n",
+            ),
+            (
+                "<em><span style=\"font-style:italic\">x</span></em>",
+                "_x_\n",
+            ),
+            (
+                "<span style=\"font-weight:700\">a<strong>b</strong></span>",
+                "**ab**\n",
+            ),
+            (
+                "<em><span style=\"font-style:italic; font-weight:bold\">x</span></em>",
+                "_**x**_\n",
+            ),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert(&source) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_pre() {
+        for (source, expected) in [
+            (
+                "<html><head></head><body><pre><code>fn main() {\n    println!(\"&lt;hi&gt;\");\n}\n</code></pre></body></html>",
+                "```\nfn main() {\n    println!(\"<hi>\");\n}\n```\n",
+            ),
+            (
+                "<html><head></head><body><pre>```\nquoted\n```</pre></body></html>",
+                "````\n```\nquoted\n```\n````\n",
+            ),
+        ] {
+            match convert(source) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_pre_in_li() {
+        let source = "<html><head></head><body><ul><li><pre><code>line1\nline2</code></pre></li></ul></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "- ```\n  line1\n  line2\n  ```\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_span() {
+        for (source, expected) in [
+            ("<body>a<span>b</span>c</body>", "abc\n"),
+            (
+                "<body>a <span style=\"font-weight: bold\">b</span>c</body>",
+                "a **b**c\n",
+            ),
+            (
+                "<body>a <span style=\"font-weight:700\">b</span>c</body>",
+                "a **b**c\n",
+            ),
+            (
+                "<body>a <span style=\"font-style: italic\">b</span>c</body>",
+                "a _b_c\n",
+            ),
+            (
+                "<body>a <span style=\"font-weight:bold;font-style:italic\">b</span></body>",
+                "a **_b_**\n",
+            ),
+            (
+                "<body>a <span style=\"font-weight:400\">b</span>c</body>",
+                "a bc\n",
+            ),
+            (
+                "<body>a <span style=\"text-decoration:line-through\">b</span>c</body>",
+                "a ~b~c\n",
+            ),
+            (
+                "<body>a <span style=\"font-weight:700;text-decoration:underline line-through;vertical-align:baseline\">b</span></body>",
+                "a **~b~**\n",
+            ),
+            (
+                "<body>a <span style=\"text-decoration:underline\">b</span>c</body>",
+                "a bc\n",
+            ),
+            (
+                "<body><b style=\"font-weight:normal;\" id=\"docs-internal-guid-1a2b\"><span style=\"font-weight:700\">b</span></b></body>",
+                "**b**\n",
+            ),
+        ] {
+            match convert(source) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_complete_table() {
+        let source =
+                "<!DOCTYPE html><html><head></head><body><table><tr><th>1,1</th><th>1,2</th></tr><tr><td>2,1</td><td>2,2</td></tr><tr><td>3,1</td><td>3,2</td></tr></table></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(
+                result,
+                "| 1,1 | 1,2 |\n|---|---|\n| 2,1 | 2,2 |\n| 3,1 | 3,2 |\n"
+            ),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_standard_table() {
+        let source =
+                "<!DOCTYPE html><html><head></head><body><table><thead><tr><th>1,1</th><th>1,2</th></tr></thead><tbody><tr><td>2,1</td><td>2,2</td></tr><tr><td>3,1</td><td>3,2</td></tr></tbody></table></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(
+                result,
+                "| 1,1 | 1,2 |\n|---|---|\n| 2,1 | 2,2 |\n| 3,1 | 3,2 |\n"
+            ),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_irregular_table() {
+        let source =
+                "<html><head></head><body><table><tr><th>1,1</th><th>1,2</th></tr><tr><td colspan=\"2\">2,1</td><td>2,3</td></tr></table></body></html>";
+        {
+            let options = Options {
+                table_fallback_html: true,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(
+                    result,
+                    "<table><tr><th>1,1</th><th>1,2</th></tr><tr><td colspan=\"2\">2,1</td><td>2,3</td></tr></table>\n"
+                ),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "| 1,1 | 1,2 |\n|---|---|\n| 2,1 | 2,3 |\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_table_by_flavor() {
+        let source = "<html><head></head><body><p>before</p><table><tr><th>1,1</th><th>1,2</th></tr><tr><td>2,1</td><td>2,2</td></tr></table></body></html>";
+        for (flavor, expected) in [
+            (
+                Flavor::Gfm,
+                "before\n\n| 1,1 | 1,2 |\n|---|---|\n| 2,1 | 2,2 |\n",
+            ),
+            (
+                Flavor::CommonMark,
+                "before\n\n<table><tr><th>1,1</th><th>1,2</th></tr><tr><td>2,1</td><td>2,2</td></tr></table>\n",
+            ),
+        ] {
+            let options = Options {
+                flavor,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_pandoc_table() {
+        let options = Options {
+            flavor: Flavor::Pandoc,
+            ..Default::default()
+        };
+        for (source, expected) in [
+            (
+                r#"<table><tr><th align="left">Name</th><th style="text-align: right">Price</th><th align="center">Stock</th><th>Note</th></tr><tr><td>apple</td><td>1</td><td>yes</td><td>red</td></tr></table>"#,
+                "| Name | Price | Stock | Note |\n|:---|---:|:---:|---|\n| apple | 1 | yes | red |\n",
+            ),
+            (
+                r#"<table><tr><th>Item</th><th align="right">Details</th></tr><tr><td>apple</td><td><p>Red fruit.</p><p>Crunchy.</p></td></tr></table>"#,
+                "+-------+------------+\n| Item  | Details    |\n+=======+===========:+\n| apple | Red fruit. |\n|       |            |\n|       | Crunchy.   |\n+-------+------------+\n",
+            ),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert_with_options(&source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_table_with_header_rows() {
+        let source = r#"<html><head></head><body><table><thead><tr><th rowspan="2">Name</th><th colspan="2">Score</th></tr><tr><th>Math</th><th>Art</th></tr></thead><tbody><tr><td>Alice</td><td>90</td><td>80</td></tr></tbody></table></body></html>"#;
+        match convert(source) {
+            Ok(result) => assert_eq!(
+                result,
+                "| Name | Score<br>Math | Score<br>Art |\n|---|---|---|\n| Alice | 90 | 80 |\n"
+            ),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_table_with_colgroup() {
+        let source = r#"<html><head></head><body><table><colgroup><col><col align="center"><col></colgroup><tr><th>a</th><th>b</th><th>c</th></tr><tr><td>1</td><td>2</td></tr></table></body></html>"#;
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "| a | b | c |\n|---|:---:|---|\n| 1 | 2 |  |\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_table_with_huge_spans() {
+        for source in [
+            r#"<table><col span="50000000"><tr><td>a</td></tr></table>"#,
+            r#"<table><tr><td colspan="50000000">a</td></tr><tr><td>b</td></tr></table>"#,
+            r#"<table><thead><tr><th rowspan="50000000" colspan="50000000">a</th></tr><tr><th>b</th></tr></thead></table>"#,
+        ] {
+            match convert(source) {
+                // Spans are clamped to at most 1000 columns.
+                Ok(result) => assert!(result.len() < 100_000),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_table_with_block_cells() {
+        let source = "<html><head></head><body><table><tr><th>a</th><th>b</th></tr><tr><td><p>one</p><p>two</p></td><td><ul><li>x</li><li>y</li></ul></td></tr></table></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(
+                result,
+                "| a | b |\n|---|---|\n| one<br>two | - x<br>- y |\n"
+            ),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_regular_table_with_spans_and_table_fallback_html() {
+        let source =
+                "<html><head></head><body><table><tr><th>1,1</th><th>1,2</th></tr><tr><td colspan=\"2\">2,1</td></tr></table></body></html>";
+        let options = Options {
+            table_fallback_html: true,
+            ..Default::default()
+        };
+        match convert_with_options(source, &options) {
+            Ok(result) => assert_eq!(result, "| 1,1 | 1,2 |\n|---|---|\n| 2,1 |\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_newline_joints() {
+        let source = "<html><head></head><body><p>hello</p><p>world</p></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "hello\n\nworld\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_table_including_p() {
+        let source = "<html><head></head><body><table><tr><th>hello</th></tr><tr><td><p>world</p></td></tr></table></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "| hello |\n|---|\n| world |\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_table_including_br() {
+        let source =
+                "<!DOCTYPE html><html><head></head><body><table><thead><tr><th>1,1</th><th>1,2</th></tr></thead><tbody><tr><td>2<br>,<br>1</td><td>2<br>,<br>2</td></tr><tr><td>3<br>,<br>1</td><td>3,2</td></tr></tbody></table></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(
+                result,
+                "| 1,1 | 1,2 |\n|---|---|\n| 2<br>,<br>1 | 2<br>,<br>2 |\n| 3<br>,<br>1 | 3,2 |\n"
+            ),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ul() {
+        let source = "<html><head></head><body><ul><li>hello</li><li>world</li></ul></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "- hello\n- world\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ol() {
+        let source = "<html><head></head><body><ol><li>hello</li><li>world</li></ol></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "1. hello\n1. world\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ul_with_br() {
+        let source = "<html><head></head><body><ul><li>hello<br>world</li></ul></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "- hello\n  world\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ol_with_br() {
+        let source = "<html><head></head><body><ol><li>hello<br>world</li></ol></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "1. hello\n   world\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ul_with_p() {
+        let source =
+            "<html><head></head><body><ul><li><p>hello</p><p>world</p></li></ul></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "- hello\n\n  world\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ol_with_p() {
+        let source =
+            "<html><head></head><body><ol><li><p>hello</p><p>world</p></li></ol></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "1. hello\n\n   world\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ul_and_ul() {
+        let source = "<html><head></head><body><ul><li><ul><li>hello</li><li>world</li></ul></li></ul></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "- - hello\n  - world\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ol_and_ol() {
+        let source = "<html><head></head><body><ol><li><ol><li>hello</li><li>world</li></ol></li></ol></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "1. 1. hello\n   1. world\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ul_and_ul_on_new_line() {
+        let source = "<html><head></head><body><ul><li><ul><li>hello</li><li>world</li></ul></li></ul></body></html>";
+        {
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "- - hello\n  - world\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let options = Options {
+                nested_list_on_new_line: true,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, "-\n  - hello\n  - world\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_three_level_ul() {
+        let source = "<html><head></head><body><ul><li>foo<ul><li>bar<ul><li>baz</li></ul></li></ul></li></ul></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "- foo\n\n  - bar\n\n    - baz\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_nested_ul_with_depth_class() {
+        let source = "<html><head></head><body><ul><li>foo<ul class=\"lst-kix_list_1-1\"><li>bar</li></ul></li></ul></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "- foo\n\n  - bar\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ol_with_depth_class_prefix() {
+        for (source, options, expected) in [
+            (
+                "<ol class=\"col-md-6\"><li>hello</li></ol>",
+                Options::default(),
+                "1. hello\n",
+            ),
+            (
+                "<ol class=\"level-1\"><li>hello</li></ol>",
+                Options {
+                    list_depth_class_prefix: Some("level-".to_string()),
+                    ..Default::default()
+                },
+                "    1. hello\n",
+            ),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert_with_options(&source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_nested_flat_list_with_depth_class() {
+        let source = "<html><head></head><body><ul class=\"foo-0\"><li>foo<ul class=\"foo-1\"><li>bar</li></ul></li></ul><ul class=\"foo-2\"><li>baz</li></ul></body></html>";
+        let options = Options {
+            list_depth_class_prefix: Some("foo-".to_string()),
+            ..Default::default()
+        };
+        match convert_with_options(source, &options) {
+            Ok(result) => assert_eq!(result, "- foo\n\n  - bar\n        - baz\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ul_and_ol() {
+        let source = "<html><head></head><body><ul><li><ol><li>hello</li><li>world</li></ol></li><li>foo</li></ul></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "- 1. hello\n  1. world\n- foo\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ol_and_ul() {
+        let source = "<html><head></head><body><ol><li><ul><li>hello</li><li>world</li></ul></li><li>foo</li></ol></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "1. - hello\n   - world\n1. foo\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ol_in_google_doc_tyle() {
+        let source = "<html><head></head><body><ol class=\"lst-kix_list_1-0\"><li>hello</li><li>world</li></ol></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "1. hello\n1. world\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_indented_ol_in_google_doc_tyle() {
+        let source = "<html><head></head><body><ol class=\"lst-kix_list_1-1\"><li>hello</li><li>world</li></ol></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "    1. hello\n    1. world\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ol_and_indented_ol_in_google_doc_tyle() {
+        let source = "<html><head></head><body><ol class=\"lst-kix_list_1-0\"><li>hello</li><li>world</li></ol><ol class=\"lst-kix_list_1-1\"><li>hello</li><li>world</li></ol></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "1. hello\n1. world\n    1. hello\n    1. world\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_p_and_ol_in_google_doc_tyle() {
+        let source = "<html><head></head><body><p>foobar</p><ol class=\"lst-kix_list_1-0\"><li>hello</li><li>world</li></ol></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "foobar\n\n1. hello\n1. world\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_ul_and_p() {
+        {
+            let source =
+                "<html><head></head><body><ul><li>hello</li></ul><p>after</p></body></html>";
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "- hello\n\nafter\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let source = "<html><head></head><body><section><ul><li>hello</li></ul><p>after</p></section></body></html>";
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "- hello\n\nafter\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_a_without_attributes() {
+        let source = "<html><head></head><body><a>hello</a></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "hello\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_a_with_href() {
+        let source =
+            "<html><head></head><body><a href=\"https://example.com\">hello</a></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "[hello](https://example.com)\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_empty_a_with_name() {
+        for (source, expected) in [
+            (
+                r#"<p>before<a name="x"></a>after</p>"#,
+                "before<a name=\"x\"></a>after\n",
+            ),
+            (
+                r#"<h2><a name="x"></a>Title</h2>"#,
+                "## <a name=\"x\"></a>Title\n",
+            ),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert(&source) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_a_with_href_and_title() {
+        for (source, expected) in [
+            (
+                r#"<a href="https://example.com" title="the example">hello</a>"#,
+                "[hello](https://example.com \"the example\")\n",
+            ),
+            (
+                r#"<a href="/a.png" title="say &quot;hi&quot;"><img src="a.png" alt="a"></a>"#,
+                "[![a](a.png)](/a.png \"say &quot;hi&quot;\")\n",
+            ),
+            (
+                r#"<a href="https://example.com" title="the example" name="top">hello</a>"#,
+                "<a href=\"https://example.com\" title=\"the example\" name=\"top\">hello</a>\n",
+            ),
+            (
+                r#"<a href="https://example.com/Docs/README" title="Read More">Docs</a>"#,
+                "[Docs](https://example.com/Docs/README \"Read More\")\n",
+            ),
+            (r#"<a title="the example">hello</a>"#, "hello\n"),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert(&source) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_a_with_name() {
+        let source = "<html><head></head><body><a name=\"world\">hello</a></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "<a name=\"world\">hello</a>\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_a_with_href_and_name() {
+        let source = "<html><head></head><body><a href=\"https://example.com\" name=\"world\">hello</a></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(
+                result,
+                "<a href=\"https://example.com\" name=\"world\">hello</a>\n"
+            ),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_img() {
+        let source = "<html><head></head><body><img src=\"https://example.com/example.png\" width=\"400\" height=\"300\"></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(
+                result,
+                "<img src=\"https://example.com/example.png\" width=\"400\" height=\"300\">\n"
+            ),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_img_with_sorted_attributes() {
+        let source = "<html><head></head><body><img src=\"https://example.com/example.png\" width=\"400\" height=\"300\"></body></html>";
+        let options = Options {
+            sort_attributes: true,
+            ..Default::default()
+        };
+        match convert_with_options(source, &options) {
+            Ok(result) => assert_eq!(
+                result,
+                "<img height=\"300\" src=\"https://example.com/example.png\" width=\"400\">\n"
+            ),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_picture() {
+        let source = "<html><head></head><body><picture><source srcset=\"x.avif\" type=\"image/avif\"><source srcset=\"x.webp\" type=\"image/webp\"><img src=\"x.png\"></picture></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "<img src=\"x.png\">\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_video_with_src() {
+        let source = "<html><head></head><body><video src=\"clip.mp4\"></video></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "[clip.mp4](clip.mp4)\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_audio_with_source() {
+        let source = "<html><head></head><body><audio><source src=\"song.ogg\" type=\"audio/ogg\">My song</audio></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "[My song](song.ogg)\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_unknown_element() {
+        let source =
+            "<html><head></head><body>hello <foo bar=\"baz\"><em>world</em></foo></body></html>";
+        for (unknown_element, expected) in [
+            (UnknownElement::Drop, "hello\n"),
+            (UnknownElement::Unwrap, "hello _world_\n"),
+            (
+                UnknownElement::RawHtml,
+                "hello <foo bar=\"baz\">_world_</foo>\n",
+            ),
+        ] {
+            let options = Options {
+                unknown_element,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_unknown_block_element_in_raw_html() {
+        let source = "<html><head></head><body>hello<custom-block class=\"foo\"><p>world</p><p><em>!</em></p></custom-block>bye</body></html>";
+        let options = Options {
+            unknown_element: UnknownElement::RawHtml,
+            ..Default::default()
+        };
+        match convert_with_options(source, &options) {
+            Ok(result) => assert_eq!(
+                result,
+                "hello\n\n<custom-block class=\"foo\">\n\nworld\n\n_!_\n\n</custom-block>\n\nbye\n"
+            ),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_custom_and_namespaced_elements() {
+        let source = "<html><head></head><body><p>a<my-widget>b</my-widget><foo:bar>c</foo:bar></p></body></html>";
+        for (unknown_element, expected) in [
+            (UnknownElement::Drop, "a\n"),
+            (UnknownElement::Unwrap, "abc\n"),
+            (
+                UnknownElement::RawHtml,
+                "a<my-widget>b</my-widget><foo:bar>c</foo:bar>\n",
+            ),
+        ] {
+            let options = Options {
+                unknown_element,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_svg_in_raw_html() {
+        let source = "<html><head></head><body><svg width=\"10\" height=\"10\"><circle r=\"4\"></circle><svg:text>hi</svg:text></svg></body></html>";
+        let options = Options {
+            unknown_element: UnknownElement::RawHtml,
+            ..Default::default()
+        };
+        match convert_with_options(source, &options) {
+            Ok(result) => assert_eq!(
+                result,
+                "<svg width=\"10\" height=\"10\"><circle r=\"4\"></circle><svg:text>hi</svg:text></svg>\n"
+            ),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_preserve_svg() {
+        let source = "<html><head></head><body><p>Logo <svg width=\"16\" viewBox=\"0 0 16 16\"><g fill=\"none\"><path d=\"M0 0L16 16\"/></g><title>Home</title></svg></p></body></html>";
+        for (preserve_svg, expected) in [
+            (false, "Logo Home\n"),
+            (
+                true,
+                "Logo <svg width=\"16\" viewbox=\"0 0 16 16\"><g fill=\"none\"><path d=\"M0 0L16 16\"></path></g><title>Home</title></svg>\n",
+            ),
+        ] {
+            let options = Options {
+                preserve_svg,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_emit_frontmatter() {
+        let source = "<html><head><title>Hello &amp; \"World\"</title><meta charset=\"utf-8\"><meta name=\"author\" content=\"Jane Doe\"><meta name=\"Description\" content=\"A  short\nPage\"></head><body><p>hello</p></body></html>";
+        for (emit_frontmatter, expected) in [
+            (false, "hello\n"),
+            (
+                true,
+                "---\ntitle: \"Hello & \\\"World\\\"\"\ndescription: \"A short Page\"\nauthor: \"Jane Doe\"\n---\n\nhello\n",
+            ),
+        ] {
+            let options = Options {
+                emit_frontmatter,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        let options = Options {
+            emit_frontmatter: true,
+            ..Default::default()
+        };
+        match convert_with_options(
+            "<html><head></head><body><p>hello</p></body></html>",
+            &options,
+        ) {
+            Ok(result) => assert_eq!(result, "hello\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_noscript() {
+        let source = "<html><head></head><body><p>a</p><noscript><img src=\"pixel.png\" alt=\"\"></noscript><p>b</p></body></html>";
+        for (render_noscript, expected) in [
+            (false, "a\n\nb\n"),
+            (true, "a\n\n<img src=\"pixel.png\" alt=\"\">\n\nb\n"),
+        ] {
+            let options = Options {
+                render_noscript,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_template() {
+        let source = "<html><head></head><body><p>a</p><template><p>b <em>c</em></p><p>d</p></template></body></html>";
+        for (template_contents, expected) in [(false, "a\n"), (true, "a\n\nb _c_\n\nd\n")] {
+            let options = Options {
+                template_contents,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_less_than_sign_in_text() {
+        let source = "<html><head></head><body><p>1 < 2</p></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "1 < 2\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_markdown_syntax_references() {
+        let source = "<html><head></head><body><p>&#42;x&#42; &#35; &#65;</p></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "&#42;x&#42; &#35; A\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_malformed_tokens_with_warnings() {
+        let source = "<html><head></head><body><p>a</>b</br>c</p></body></html>";
+        match convert_with_warnings(source, &Options::default()) {
+            Ok((result, warnings)) => {
+                assert_eq!(result, "abc\n");
+                assert_eq!(
+                    warnings,
+                    vec!["2 malformed tokens are ignored.".to_string()]
+                );
+            }
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_unknown_element_with_warnings() {
+        let source = "<html><head></head><body><foo>hello</foo><p>world</p></body></html>";
+        match convert_with_warnings(source, &Options::default()) {
+            Ok((result, warnings)) => {
+                assert_eq!(result, "hello\n\nworld\n");
+                assert_eq!(
+                    warnings,
+                    vec!["`foo` element is not supported. rendering its children.".to_string()]
+                );
+            }
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_img_with_boolean_attribute() {
+        let source = "<html><head></head><body><img src=\"x.png\" ismap></body></html>";
+        {
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "<img src=\"x.png\" ismap=\"ismap\">\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let options = Options {
+                bare_boolean_attributes: true,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, "<img src=\"x.png\" ismap>\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_entity() {
+        {
+            let source = "<body>&#x3042;&#x3044;&#x3046;&#x3048;&#x304A; Foo &#x304B;&#x304D;&#x304F;&#x3051;&#x3053; Bar</body>";
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "あいうえお Foo かきくけこ Bar\n"),
+                Err(e) => panic!(
+                    "Expected Ok(\"あいうえお Foo かきくけこ Bar\") but got Err({:?})",
+                    e
+                ),
+            }
+        }
+        {
+            let source = "<html><head></head><body>&nbsp;</body></html>";
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "&nbsp;\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let source = "<html><head></head><body>&#1234;</body></html>";
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "Ӓ\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let source = "<html><head></head><body>&#xd06;</body></html>";
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "ആ\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_selector() {
+        let source = "<html><head></head><body><nav><a href=\"/\">home</a></nav><div id=\"content\"><h1>Title</h1><p class=\"lead\">Lead</p><p>Body</p></div></body></html>";
+        for (selector, expected) in [
+            ("#content", "# Title\n\nLead\n\nBody\n"),
+            (".lead", "Lead\n"),
+            ("h1", "# Title\n"),
+        ] {
+            match convert_selector(source, selector) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+
+        for selector in ["#missing", "div p"] {
+            if let Ok(result) = convert_selector(source, selector) {
+                panic!("Expected Err but got Ok({:?})", result);
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_ignore_selectors() {
+        let source = "<html><head></head><body><nav>menu</nav><p>one</p><div class=\"ad x\"><p>buy</p></div><table><tr><th>h</th></tr><tr class=\"ad\"><td>buy</td></tr><tr><td>d</td></tr></table><p>two</p></body></html>";
+        let options = Options {
+            ignore_selectors: vec![".ad".to_string(), "nav".to_string()],
+            ..Default::default()
+        };
+        match convert_with_options(source, &options) {
+            Ok(result) => assert_eq!(result, "one\n\n| h |\n|---|\n| d |\n\ntwo\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_sections() {
+        let source = "<html><head></head><body><header>Site</header><main><section><p>a</p><p>b</p></section></main><footer>Copyright</footer></body></html>";
+        {
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "a\n\nb\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let options = Options {
+                annotate_sections: true,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(
+                    result,
+                    "<!-- header -->\n\nSite\n\n<!-- /header -->\n\n<!-- main -->\n\n<!-- section -->\n\na\n\nb\n\n<!-- /section -->\n\n<!-- /main -->\n\n<!-- footer -->\n\nCopyright\n\n<!-- /footer -->\n"
+                ),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_base_url() {
+        let source = "<html><head></head><body><a href=\"https://other.com/\">a</a><a href=\"/about\">b</a><a href=\"../faq.html\">c</a><a href=\"#top\">d</a><img src=\"img/logo.png\" alt=\"logo\"></body></html>";
+        {
+            match convert(source) {
+                Ok(result) => assert_eq!(
+                    result,
+                    "[a](https://other.com/)[b](/about)[c](../faq.html)[d](#top)<img src=\"img/logo.png\" alt=\"logo\">\n"
+                ),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let options = Options {
+                base_url: Some("https://example.com/docs/guide/".to_string()),
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(
+                    result,
+                    "[a](https://other.com/)[b](https://example.com/about)[c](https://example.com/docs/faq.html)[d](#top)<img src=\"https://example.com/docs/guide/img/logo.png\" alt=\"logo\">\n"
+                ),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_base_url_keeps_special_links() {
+        let options = Options {
+            base_url: Some("https://example.com/docs/".to_string()),
+            ..Default::default()
+        };
+        for (href, expected) in [
+            ("//cdn.example.com/x", "https://cdn.example.com/x"),
+            ("mailto:a@b.com", "mailto:a@b.com"),
+            ("tel:+123", "tel:+123"),
+            ("page.html", "https://example.com/docs/page.html"),
+        ] {
+            let source = format!(
+                "<html><head></head><body><a href=\"{}\">link</a></body></html>",
+                href
+            );
+            match convert_with_options(&source, &options) {
+                Ok(result) => assert_eq!(result, format!("[link]({})\n", expected)),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_autolinks() {
+        let options = Options {
+            autolinks: true,
+            ..Default::default()
+        };
+        for (source, expected) in [
+            (
+                "<a href=\"https://x.com\">https://x.com</a>",
+                "<https://x.com>\n",
+            ),
+            (
+                "<a href=\"https://x.com/\">https://x.com</a>",
+                "[https://x.com](https://x.com/)\n",
+            ),
+            (
+                "<a href=\"https://x.com\">x.com</a>",
+                "[x.com](https://x.com)\n",
+            ),
+            ("<a href=\"/about\">/about</a>", "[/about](/about)\n"),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert_with_options(&source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_a_wrapping_blocks() {
+        for (source, expected) in [
+            (
+                "<a href=\"x\"><div><h2>Title</h2><p>Text</p></div></a>",
+                "<a href=\"x\">\n\n## Title\n\nText\n\n</a>\n",
+            ),
+            ("<a href=\"x\"><img src=\"y\"></a>", "[![](y)](x)\n"),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert(&source) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_img_in_a() {
+        for (source, expected) in [
+            (
+                "<a href=\"page\"><img src=\"thumb.png\" alt=\"t\"></a>",
+                "[![t](thumb.png)](page)\n",
+            ),
+            (
+                "<a href=\"Pages/Cat\"><img src=\"Images/Cat.PNG\" alt=\"A Cat\"></a>",
+                "[![A Cat](Images/Cat.PNG)](Pages/Cat)\n",
+            ),
+            (
+                "<a href=\"page\">see <img src=\"thumb.png\"></a>",
+                "[see <img src=\"thumb.png\">](page)\n",
+            ),
+            (
+                "<a href=\"page\">\n  <img src=\"thumb.png\" alt=\"t\">\n</a>",
+                "[![t](thumb.png)](page)\n",
+            ),
+            (
+                "<a href=\"page\"><img src=\"my thumb (1).png\" alt=\"a]b\"></a>",
+                "[![a\\]b](<my thumb (1).png>)](page)\n",
+            ),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert(&source) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_details() {
+        let source = "<html><head></head><body><details><summary>More</summary><p>a</p><p>b</p></details></body></html>";
+        for (details, expected) in [
+            (Details::Unwrap, "More\n\na\n\nb\n"),
+            (
+                Details::Html,
+                "<details>\n<summary>More</summary>\n\na\n\nb\n\n</details>\n",
+            ),
+            (Details::Markdown, "**More**\n\n> a\n>\n> b\n"),
+            (Details::Heading, "### More\n\na\n\nb\n"),
+        ] {
+            let options = Options {
+                details,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_details_as_faq_headings() {
+        let source = "<html><head></head><body><h2>FAQ</h2><details><summary>Is it <em>free</em>?</summary><p>Yes.</p></details><details><summary>Can I\ncontribute?</summary><p>Sure.</p><ul><li>Fork</li></ul></details><details><p>No summary.</p></details></body></html>";
+        let options = Options {
+            details: Details::Heading,
+            ..Default::default()
+        };
+        match convert_with_options(source, &options) {
+            Ok(result) => assert_eq!(
+                result,
+                "## FAQ\n\n### Is it _free_?\n\nYes.\n\n### Can I contribute?\n\nSure.\n\n- Fork\n\nNo summary.\n"
+            ),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_inline_content_after_block() {
+        for (source, expected) in [
+            ("<div><p>a</p>loose text</div>", "a\n\nloose text\n"),
+            (
+                "<p>a</p><strong>bold</strong>!<p>b</p>",
+                "a\n\n**bold**!\n\nb\n",
+            ),
+            ("<p>a</p>\n<p>b</p>", "a\n\nb\n"),
+            ("<ul><li>1</li></ul>after", "- 1\n\nafter\n"),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert(&source) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_text_and_blocks_in_any_order() {
+        for (source, expected) in [
+            (
+                "<blockquote><div>text<p>a</p></div></blockquote>",
+                "> text\n>\n> a\n",
+            ),
+            (
+                "<blockquote><div><p>a</p>text</div></blockquote>",
+                "> a\n>\n> text\n",
+            ),
+            (
+                "<blockquote><div><p>a</p><p>b</p></div></blockquote>",
+                "> a\n>\n> b\n",
+            ),
+            (
+                "<blockquote><br><span> </span><p>a</p></blockquote>",
+                "> a\n",
+            ),
+            (
+                "<ul><li><span></span><p>a</p>text</li></ul>",
+                "- a\n\n  text\n",
+            ),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert(&source) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_q_citation() {
+        let source = "<html><head></head><body><p>He said <q cite=\"/talks/1\">stay <em>hungry</em></q>, and <q>smiled</q>.</p></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "He said \"stay _hungry_\", and \"smiled\".\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+        for (q_citation, expected) in [
+            (false, "He said \"stay _hungry_\", and \"smiled\".\n"),
+            (
+                true,
+                "He said \"stay _hungry_\"[source](https://example.com/talks/1), and \"smiled\".\n",
+            ),
+        ] {
+            let options = Options {
+                q_citation,
+                base_url: Some("https://example.com/".to_string()),
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_separate_successive_lists() {
+        let source = "<html><head></head><body><p>x</p><ul><li>a</li></ul><ol><li>b</li></ol><p>y</p></body></html>";
+        for (separate_successive_lists, expected) in [
+            (false, "x\n\n- a\n1. b\n\ny\n"),
+            (true, "x\n\n- a\n\n1. b\n\ny\n"),
+        ] {
+            let options = Options {
+                separate_successive_lists,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_whitespace_at_block_edges() {
+        for (source, expected) in [
+            ("<p>  hello  </p>", "hello\n"),
+            ("<div>hello \n </div>", "hello\n"),
+            ("<pre>code  </pre>", "```\ncode  \n```\n"),
+            ("<p>a<br>  </p><p>b</p>", "a\n\nb\n"),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert(&source) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_heading_ids() {
+        let source =
+            "<html><head></head><body><h2 id=\"install\">Install</h2><h3>Usage</h3></body></html>";
+        {
+            match convert(source) {
+                Ok(result) => assert_eq!(result, "## Install\n\n### Usage\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+        {
+            let options = Options {
+                heading_ids: true,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, "## Install {#install}\n\n### Usage\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+            let source =
+                "<html><head></head><body><h2 id=\"Install-Guide\">Install</h2></body></html>";
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, "## Install {#Install-Guide}\n"),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_heading_style() {
+        let source =
+            "<html><head></head><body><h1>Title</h1><h2>Sub</h2><h3>Deep</h3></body></html>";
+        for (heading_style, expected) in [
+            (HeadingStyle::Atx, "# Title\n\n## Sub\n\n### Deep\n"),
+            (
+                HeadingStyle::Setext,
+                "Title\n=====\n\nSub\n---\n\n### Deep\n",
+            ),
+        ] {
+            let options = Options {
+                heading_style,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_heading_on_one_line() {
+        for (source, expected) in [
+            ("<h1>a<br>b</h1>", "# a b\n"),
+            ("<h2><span>a</span><br><br>b</h2>", "## a b\n"),
+            ("<h3>a<br>\n<br>b<br></h3>", "### a b\n"),
+            ("<h4><em>a<br>b</em></h4>", "#### _a b_\n"),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert(&source) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_list_type_markers() {
+        let options = Options {
+            list_type_markers: true,
+            ..Default::default()
+        };
+        for (source, expected) in [
+            ("<ol type=\"a\"><li>x</li><li>y</li></ol>", "a. x\nb. y\n"),
+            (
+                "<ol type=\"i\" start=\"3\"><li>x</li><li>y</li></ol>",
+                "iii. x\niv. y\n",
+            ),
+            ("<ol type=\"A\"><li>x</li><li>y</li></ol>", "A. x\nB. y\n"),
+            (
+                "<ol type=\"I\" start=\"3\"><li>x</li><li>y</li></ol>",
+                "III. x\nIV. y\n",
+            ),
+            ("<ol><li>x</li><li>y</li></ol>", "1. x\n1. y\n"),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert_with_options(&source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+
+        let source = "<html><head></head><body><ol type=\"a\"><li>x</li></ol></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "1. x\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_deeply_nested() {
+        let nested = |depth: usize| {
+            format!(
+                "<html><head></head><body>{}x{}</body></html>",
+                "<div>".repeat(depth),
+                "</div>".repeat(depth)
+            )
+        };
+
+        match convert(&nested(10_000)) {
+            Ok(result) => panic!("Expected Err but got Ok({:?})", result),
+            Err(e) => assert_eq!(e.to_string(), "elements are nested too deeply"),
+        }
+        match convert(&nested(500)) {
+            Ok(result) => assert_eq!(result, "x\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+
+        let options = Options {
+            max_depth: Some(10),
+            ..Default::default()
+        };
+        if let Ok(result) = convert_with_options(&nested(10), &options) {
+            panic!("Expected Err but got Ok({:?})", result);
+        }
+    }
+
+    #[test]
+    fn test_convert_max_input_bytes() {
+        let source = "<html><head></head><body><p>hello</p></body></html>";
+        let options = Options {
+            max_input_bytes: Some(source.len()),
+            ..Default::default()
+        };
+        match convert_with_options(source, &options) {
+            Ok(result) => assert_eq!(result, "hello\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+
+        let options = Options {
+            max_input_bytes: Some(source.len() - 1),
+            ..Default::default()
+        };
+        match convert_with_options(source, &options) {
+            Ok(result) => panic!("Expected Err but got Ok({:?})", result),
+            Err(e) => assert_eq!(e.to_string(), "input of 51 bytes exceeds limit of 50"),
+        }
+    }
+
+    #[test]
+    fn test_convert_blockquote_attribution() {
+        let options = Options {
+            blockquote_attribution: true,
+            ..Default::default()
+        };
+        for (source, expected) in [
+            (
+                r#"<blockquote cite="https://example.com/quote"><p>quote</p></blockquote>"#,
+                "> quote\n>\n> — https://example.com/quote\n",
+            ),
+            (
+                "<blockquote><p>quote</p><cite>Author</cite></blockquote>",
+                "> quote\n>\n> — Author\n",
+            ),
+            (
+                "<blockquote><p>quote</p><footer><p>Author, <em>Title</em></p></footer></blockquote>",
+                "> quote\n>\n> — Author, _Title_\n",
+            ),
+            ("<blockquote><p>quote</p></blockquote>", "> quote\n"),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert_with_options(&source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_blockquote_footer() {
+        for (source, expected) in [
+            (
+                "<blockquote><p>quote</p><footer>Author</footer></blockquote>",
+                "> quote\n>\n> — Author\n",
+            ),
+            (
+                r#"<blockquote cite="https://example.com/quote"><p>quote</p></blockquote>"#,
+                "> quote\n",
+            ),
+            ("<footer>Author</footer>", "\n"),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert(&source) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_paragraph_alignment() {
+        let options = Options {
+            paragraph_alignment: true,
+            ..Default::default()
+        };
+        for (source, expected) in [
+            (
+                r#"<p align="center">Title <em>here</em></p>"#,
+                "<div align=\"center\">\n\nTitle _here_\n\n</div>\n",
+            ),
+            (
+                r#"<p style="color: red; text-align: right">Right</p>"#,
+                "<div align=\"right\">\n\nRight\n\n</div>\n",
+            ),
+            (r#"<p align="middle">Plain</p>"#, "Plain\n"),
+            ("<p>Plain</p>", "Plain\n"),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert_with_options(&source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+
+        let source = r#"<html><head></head><body><p align="center">Title</p></body></html>"#;
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "Title\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_small() {
+        let source = "<html><head></head><body><p>Price <small>excl. tax</small></p></body></html>";
+        for (small_html, expected) in [
+            (false, "Price excl. tax\n"),
+            (true, "Price <small>excl. tax</small>\n"),
+        ] {
+            let options = Options {
+                small_html,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_menu() {
+        let source = "<html><head></head><body><p>Tools</p><menu><li>Copy</li><li>Paste<ul><li>Plain</li></ul></li></menu></body></html>";
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "Tools\n\n- Copy\n- Paste\n\n  - Plain\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_li_outside_of_list() {
+        for (source, expected) in [
+            ("<li>hello</li>", "- hello\n"),
+            (
+                "<p>para</p><li>hello</li><li>world</li>",
+                "para\n\n- hello\n\n- world\n",
+            ),
+            (
+                "<div><li>hello<ul><li>world</li></ul></li></div>",
+                "- hello\n\n  - world\n",
+            ),
+        ] {
+            let source = format!("<html><head></head><body>{}</body></html>", source);
+            match convert(&source) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_mso_list_paragraphs() {
+        let source = r#"<html><head></head><body><p class="MsoNormal">Steps</p>
+<p class="MsoListParagraphCxSpFirst" style="text-indent:-.25in;mso-list:l0 level1 lfo1"><![if !supportLists]><span style="mso-list:Ignore">1.<span style="font:7.0pt &quot;Times New Roman&quot;">&nbsp;&nbsp;</span></span><![endif]>Open</p>
+<p class="MsoListParagraphCxSpLast" style="text-indent:-.25in;mso-list:l0 level1 lfo1"><![if !supportLists]><span style="mso-list:Ignore">2.<span style="font:7.0pt &quot;Times New Roman&quot;">&nbsp;&nbsp;</span></span><![endif]>Close</p>
+</body></html>"#;
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "Steps\n\n1. Open\n1. Close\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_mso_list_paragraphs_exported_by_word() {
+        let source = include_str!("../tests/fixtures/word/list.html");
+        match convert(source) {
+            Ok(result) => assert_eq!(result, "Steps\n\n1. Open\n1. Close\n"),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_flavor() {
+        let source = "<html><head></head><body><p>a <del>b</del></p><p>c <ins>d</ins></p><dl><dt>term</dt><dd>definition</dd></dl></body></html>";
+        for (flavor, expected) in [
+            (
+                Flavor::Gfm,
+                "a ~b~\n\nc <ins>d</ins>\n\nterm\n: definition\n",
+            ),
+            (
+                Flavor::CommonMark,
+                "a <del>b</del>\n\nc <ins>d</ins>\n\n<dl><dt>term</dt><dd>definition</dd></dl>\n",
+            ),
+            (
+                Flavor::Pandoc,
+                "a ~~b~~\n\nc <ins>d</ins>\n\nterm\n: definition\n",
+            ),
+        ] {
+            let options = Options {
+                flavor,
+                ..Default::default()
+            };
+            match convert_with_options(source, &options) {
+                Ok(result) => assert_eq!(result, expected),
+                Err(e) => panic!("Unexpected Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_to_plain_text() {
+        let source = r#"<html><head><title>Doc</title><style>p { color: red; }</style></head><body><h2>Install &amp; run</h2><p>See <a href="https://example.com">the <em>guide</em></a>.</p><blockquote><p>quoted</p></blockquote><ol><li>one</li><li><code>two</code></li></ol><pre><code>let x = 1;
+let y = 2;</code></pre><table><tr><th>k</th><th>v</th></tr><tr><td>a</td><td><strong>1</strong></td></tr></table><img src="a.png" alt="picture"></body></html>"#;
+        match convert_to_plain_text(source) {
+            Ok(result) => assert_eq!(
+                result,
+                "Install & run\n\nSee the guide.\n\nquoted\n\none\ntwo\n\nlet x = 1;\nlet y = 2;\n\nk\tv\na\t1\n\npicture\n"
+            ),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_large_document() {
+        let section = "<section><h2>Title</h2><p>Some <strong>bold</strong>.</p><ul><li>one</li><li>two</li></ul></section>";
+        let source = format!(
+            "<html><head></head><body>{}</body></html>",
+            section.repeat(5_000)
+        );
+        let expected = "## Title\n\nSome **bold**.\n\n- one\n- two\n".repeat(5_000);
+        let expected = expected.replace("two\n## Title", "two\n\n## Title");
+        match convert(&source) {
+            Ok(result) => assert_eq!(result, expected),
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_convert_never_panics() {
+        let document = "<!DOCTYPE html><html><head><title>t</title></head><body><p>&#x41;&日本; <b>b</b></p><ul><li>1<ol class=\"lst-kix_list_1-1\"><li>2</li></ol></li></ul><table><tr><th colspan=\"2\">h</th></tr><tr><td>d</td></tr></table><pre><code>x</code></pre><br/></body></html>";
+        let mut sources: Vec<&str> = (0..=document.len())
+            .filter(|&end| document.is_char_boundary(end))
+            .map(|end| &document[..end])
+            .collect();
+        sources.extend([
+            "<html></html>",
+            "<html><head></head></html>",
+            "<body><thead></thead><tr><td>x</td></tr><li>o</li></body>",
+            "<a#>",
+            "<p>&#x日;&#日;</p>",
+        ]);
+
+        for source in sources {
+            // Any result is fine as long as it returns.
+            let _ = convert(source);
+        }
+    }
+}
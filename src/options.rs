@@ -0,0 +1,204 @@
+// The default of Options::max_depth, which is safe for the stack of a
+// spawned thread.
+pub const DEFAULT_MAX_DEPTH: usize = 512;
+
+// The default of Options::list_depth_class_prefix, which Google Docs uses.
+pub const DEFAULT_LIST_DEPTH_CLASS_PREFIX: &str = "lst-";
+
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    // The Markdown dialect of extensions like strikethrough. Constructs which
+    // the flavor lacks are kept in HTML form.
+    pub flavor: Flavor,
+
+    // Emit tables whose rows have varying column counts (after colspan/rowspan
+    // expansion) as raw HTML instead of a Markdown grid.
+    pub table_fallback_html: bool,
+
+    // Put a nested list which begins a list item on the line after the
+    // parent's marker instead of right after it, like `-\n  - item`.
+    pub nested_list_on_new_line: bool,
+
+    // Mark items of <ol type="a"> and <ol type="i"> like `a.` and `i.`
+    // instead of `1.`.
+    pub list_type_markers: bool,
+
+    // End a <blockquote> with an attribution line like `> — Author` taken from
+    // a trailing <cite> child or else the cite attribute, as is always done
+    // for a trailing <footer> child.
+    pub blockquote_attribution: bool,
+
+    // Wrap a <p> aligned by the align attribute or text-align in the style
+    // attribute in an aligned <div>, like `<div align="center">`.
+    pub paragraph_alignment: bool,
+
+    // Indent a list by the number at the end of a class with this prefix,
+    // which is DEFAULT_LIST_DEPTH_CLASS_PREFIX when not given, like four
+    // spaces for `lst-kix_abc-1`.
+    pub list_depth_class_prefix: Option<String>,
+
+    // Separate successive <ul> and <ol> with blank lines like other blocks
+    // instead of putting them on adjacent lines.
+    pub separate_successive_lists: bool,
+
+    pub wbr: Wbr,
+
+    pub address: Address,
+
+    // Append the datetime attribute of <time>, like `Jan 1 (2020-01-01)`.
+    pub time_datetime: bool,
+
+    // Keep <data> in HTML form so that its value attribute survives, like
+    // `<data value="42">forty-two</data>`.
+    pub data_html: bool,
+
+    pub ins: Ins,
+
+    pub ruby: Ruby,
+
+    // Follow <q>, which is put in quotation marks, by a link to its cite
+    // attribute, like `"quote"[source](https://example.com)`.
+    pub q_citation: bool,
+
+    // Keep <small> in HTML form, like `<small>fine print</small>`.
+    pub small_html: bool,
+
+    // Keep <svg> with its whole subtree in HTML form, like
+    // `<svg><path d="M0 0L1 1"></path></svg>`.
+    pub preserve_svg: bool,
+
+    pub details: Details,
+
+    // Render the inert contents of <template> like a <div> instead of dropping
+    // them.
+    pub template_contents: bool,
+
+    // Render the contents of <noscript> like a <div> instead of dropping them,
+    // which keeps fallbacks like a plain <img> of pages relying on scripts.
+    pub render_noscript: bool,
+
+    // Mark the boundaries of <article>, <aside>, <footer>, <header>, <main>,
+    // <nav> and <section> with comments like `<!-- section -->` and
+    // `<!-- /section -->` instead of unwrapping them. <header> and <footer>
+    // are rendered only in this mode.
+    pub annotate_sections: bool,
+
+    // Begin with YAML frontmatter of the <title> and the description, author
+    // and keywords <meta> in <head>, like `---\ntitle: "Home"\n---\n`.
+    pub emit_frontmatter: bool,
+
+    pub trailing_newline: TrailingNewline,
+
+    pub unknown_element: UnknownElement,
+
+    // Append the id attribute of headings, like `## Install {#install}`.
+    pub heading_ids: bool,
+
+    pub heading_style: HeadingStyle,
+
+    // Emit <hr> as this thematic break instead of `---`, like `***`, `___` or
+    // `- - -`. Anything else is an error when rendering.
+    pub thematic_break: Option<String>,
+
+    // Treat successive <br> as a paragraph break, ignoring more than two.
+    pub coalesce_br: bool,
+
+    // Emit an attribute whose value equals its name, like `disabled` parsed
+    // from `<input disabled>`, without the value in HTML form.
+    pub bare_boolean_attributes: bool,
+
+    // Emit attributes in alphabetical order instead of source order in HTML
+    // form.
+    pub sort_attributes: bool,
+
+    // Emit `<https://x.com>` for a link whose text is exactly its absolute
+    // URL instead of `[https://x.com](https://x.com)`.
+    pub autolinks: bool,
+
+    // Resolve relative href of <a> and src of <img>, <audio> and <video>
+    // against this URL, like `https://example.com/docs/`.
+    pub base_url: Option<String>,
+
+    // Maximum nesting depth of elements, which is DEFAULT_MAX_DEPTH when not
+    // given. Deeper input is an error instead of overflowing the stack.
+    pub max_depth: Option<usize>,
+
+    // Reject a source longer than this many bytes before tokenizing it.
+    pub max_input_bytes: Option<usize>,
+
+    // Remove elements matching any of these `#id`, `.class` or `tag`
+    // selectors with their contents, like `vec![".ad".to_string()]`. Invalid
+    // selectors match nothing.
+    pub ignore_selectors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Address {
+    #[default]
+    Plain,
+    Italic,
+    Blockquote,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Wbr {
+    #[default]
+    Nothing,
+    ZeroWidthSpace, // U+200B
+    SoftHyphen,     // U+00AD
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Ins {
+    #[default]
+    Html, // <ins>text</ins>
+    PlusPlus, // ++text++, which is not a part of CommonMark nor GFM
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TrailingNewline {
+    None,
+    #[default]
+    Single,
+    Preserve, // as rendered
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum UnknownElement {
+    Drop,
+    #[default]
+    Unwrap, // render children only
+    RawHtml, // render the element in HTML form
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Details {
+    #[default]
+    Unwrap, // render children only
+    Html,     // <details> block with <summary> in HTML form
+    Markdown, // **summary** followed by the body in a blockquote
+    Heading,  // ### summary followed by the body, like an FAQ
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum HeadingStyle {
+    #[default]
+    Atx, // # Title
+    Setext, // Title\n===== for level 1 and 2
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Flavor {
+    CommonMark,
+    #[default]
+    Gfm, // GitHub Flavored Markdown
+    Pandoc,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Ruby {
+    #[default]
+    Base, // base text only
+    Parentheses, // base(reading)
+    Html,        // <ruby>base<rt>reading</rt></ruby>
+}
@@ -3,11 +3,13 @@ use std::iter::Peekable;
 use std::slice::Iter;
 
 use crate::ast::{Element, Node, Tag, TagKind, Token};
+use crate::options::DEFAULT_MAX_DEPTH;
 
 pub type Result<T> = std::result::Result<T, ParseError>;
 
 #[derive(Debug, PartialEq)]
 pub enum ParseError {
+    TooDeep,
     UnexpectedEOF,
     UnexpectedToken,
 }
@@ -15,6 +17,7 @@ pub enum ParseError {
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
+            ParseError::TooDeep => write!(f, "elements are nested too deeply"),
             ParseError::UnexpectedEOF => write!(f, "unexpected EOF"),
             ParseError::UnexpectedToken => write!(f, "unexpected token"),
         }
@@ -24,23 +27,37 @@ impl fmt::Display for ParseError {
 impl std::error::Error for ParseError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match *self {
+            ParseError::TooDeep => None,
             ParseError::UnexpectedEOF => None,
             ParseError::UnexpectedToken => None,
         }
     }
 }
 
-pub struct Parser<'a> {
-    tokens: Peekable<Iter<'a, Token>>,
+// Parses tokens borrowed for 'a into nodes which borrow the source for 's.
+pub struct Parser<'a, 's> {
+    tokens: Peekable<Iter<'a, Token<'s>>>,
+    depth: usize,
+    max_depth: usize,
 }
 
-impl<'a> Parser<'a> {
-    pub fn new(tokens: &'a [Token]) -> Self {
+impl<'a, 's> Parser<'a, 's> {
+    pub fn new(tokens: &'a [Token<'s>]) -> Self {
+        Self::with_max_depth(tokens, DEFAULT_MAX_DEPTH)
+    }
+
+    // Elements nested deeper than max_depth are an error instead of
+    // overflowing the stack.
+    pub fn with_max_depth(tokens: &'a [Token<'s>], max_depth: usize) -> Self {
         let it = tokens.iter().peekable();
-        Self { tokens: it }
+        Self {
+            tokens: it,
+            depth: 0,
+            max_depth,
+        }
     }
 
-    pub fn parse(&mut self) -> Result<Node> {
+    pub fn parse(&mut self) -> Result<Node<'s>> {
         self.expect_element()
     }
 
@@ -58,11 +75,16 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn expect_element(&mut self) -> Result<Node> {
+    fn expect_element(&mut self) -> Result<Node<'s>> {
         match self.tokens.next() {
             Some(Token::Tag(tag)) => match tag.kind {
                 TagKind::Open => {
+                    if self.depth >= self.max_depth {
+                        return Err(ParseError::TooDeep);
+                    }
+                    self.depth += 1;
                     let children = self.element_or_text_nodes()?;
+                    self.depth -= 1;
                     let _close_tag = self.expect_close_tag_with_name(&tag.name)?;
                     Ok(Node::Element(Element::new_with_children(
                         &tag.name,
@@ -78,15 +100,15 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn expect_text(&mut self) -> Result<Node> {
+    fn expect_text(&mut self) -> Result<Node<'s>> {
         match self.tokens.next() {
-            Some(Token::Text(content)) => Ok(Node::Text(content.to_string())),
+            Some(Token::Text(content)) => Ok(Node::Text(content.clone())),
             Some(_) => Err(ParseError::UnexpectedToken),
             None => Err(ParseError::UnexpectedEOF),
         }
     }
 
-    fn element_or_text_nodes(&mut self) -> Result<Vec<Node>> {
+    fn element_or_text_nodes(&mut self) -> Result<Vec<Node<'s>>> {
         let mut nodes = Vec::new();
 
         loop {
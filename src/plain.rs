@@ -0,0 +1,136 @@
+use crate::ast::{is_block_element, Element, Node};
+use crate::render::decode_plain_text;
+
+// Renders the text of a tree returned by `restruct` without Markdown syntax.
+// Blocks are separated with blank lines, and list items and table rows are put
+// on their own lines with cells separated by tabs.
+pub fn render_plain(node: &Node) -> String {
+    let mut content = String::new();
+    render_node(node, &mut content, false);
+
+    let mut lines: Vec<&str> = Vec::new();
+    for line in content.split('\n').map(str::trim_end) {
+        if line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|last| last.is_empty()) {
+        lines.pop();
+    }
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    result
+}
+
+// A list in an item is put on the next line instead of after a blank line.
+fn render_node(node: &Node, buffer: &mut String, in_item: bool) {
+    match node {
+        Node::Element(element) => render_element(element, buffer, in_item),
+        Node::Text(content) => buffer.push_str(&decode_plain_text(content)),
+    }
+}
+
+fn render_element(element: &Element, buffer: &mut String, in_item: bool) {
+    match element.tag_name.as_str() {
+        "br" => buffer.push('\n'),
+        "img" => {
+            if let Some(alt) = element.attributes.get("alt") {
+                buffer.push_str(alt);
+            }
+        }
+        "pre" => {
+            break_block(buffer);
+            let text: String = element.children.iter().map(Node::text_content).collect();
+            buffer.push_str(&decode_plain_text(&text));
+            break_block(buffer);
+        }
+        "dd" | "dt" | "li" => {
+            break_line(buffer);
+            render_children(element, buffer);
+            break_line(buffer);
+        }
+        "tr" => {
+            break_line(buffer);
+            let cells = element.children.iter().filter_map(|node| match node {
+                Node::Element(cell) if cell.tag_name == "th" || cell.tag_name == "td" => Some(cell),
+                _ => None,
+            });
+            for (i, cell) in cells.enumerate() {
+                if i > 0 {
+                    buffer.push('\t');
+                }
+                render_children(cell, buffer);
+            }
+            break_line(buffer);
+        }
+        "audio" | "button" | "canvas" | "embed" | "head" | "iframe" | "input" | "noscript"
+        | "object" | "script" | "select" | "style" | "template" | "textarea" | "video" => {}
+        "html2md:successive-lists-wrapper" if in_item => {
+            break_line(buffer);
+            for child in &element.children {
+                render_node(child, buffer, in_item);
+            }
+        }
+        _ if in_item && element.is_list_element() => {
+            break_line(buffer);
+            render_children(element, buffer);
+        }
+        tag_name if is_block_element(tag_name) => {
+            break_block(buffer);
+            render_children(element, buffer);
+            break_block(buffer);
+        }
+        _ => render_children(element, buffer),
+    }
+}
+
+fn render_children(element: &Element, buffer: &mut String) {
+    let in_item = element.tag_name == "li";
+    for child in &element.children {
+        render_node(child, buffer, in_item);
+    }
+}
+
+fn break_line(buffer: &mut String) {
+    if !buffer.is_empty() && !buffer.ends_with('\n') {
+        buffer.push('\n');
+    }
+}
+
+fn break_block(buffer: &mut String) {
+    break_line(buffer);
+    if !buffer.is_empty() && !buffer.ends_with("\n\n") {
+        buffer.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::Options;
+    use crate::{parse_html, restruct};
+
+    #[test]
+    fn test_render_plain() {
+        let source = "<html><head><title>t</title></head><body><h1>Title</h1><p>a <b>b</b><br>c</p><ul><li>x<ul><li>y</li></ul></li></ul><table><tr><th>1</th><th>2</th></tr></table></body></html>";
+        let node = match parse_html(source) {
+            Ok(node) => node,
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        };
+        let node = restruct(&node, &Options::default());
+        assert_eq!(render_plain(&node), "Title\n\na b\nc\n\nx\ny\n\n1\t2\n");
+    }
+
+    #[test]
+    fn test_render_plain_with_named_entities() {
+        let source = "<html><head></head><body><p>a&nbsp;b &copy; c &amp;lt;</p></body></html>";
+        let node = match parse_html(source) {
+            Ok(node) => node,
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        };
+        let node = restruct(&node, &Options::default());
+        assert_eq!(render_plain(&node), "a\u{A0}b \u{A9} c &lt;\n");
+    }
+}
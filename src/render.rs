@@ -1,18 +1,79 @@
+use std::collections::HashMap;
 use std::fmt;
+use std::rc::Rc;
 
 use crate::ast::{is_block_element, is_void_element, Element, Node};
+use crate::options::{
+    Address, Details, Flavor, HeadingStyle, Ins, Options, Ruby, TrailingNewline, UnknownElement,
+    Wbr, DEFAULT_LIST_DEPTH_CLASS_PREFIX, DEFAULT_MAX_DEPTH,
+};
+use crate::restruct::span_attribute;
+use crate::serialize::{open_tag, serialize_html_with_options};
+use crate::url::{has_scheme, resolve_url};
 
 pub type Result<T> = std::result::Result<T, RenderError>;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Emphasis {
+    Strong,
+    Em,
+    Del,
+}
+
+// The emphases given by the tag name, or by the style attribute of <span>
+// like `font-weight: bold`.
+fn emphases(element: &Element) -> Vec<Emphasis> {
+    match element.tag_name.as_str() {
+        "strong" => vec![Emphasis::Strong],
+        "em" => vec![Emphasis::Em],
+        "del" => vec![Emphasis::Del],
+        "span" => {
+            let mut emphases = Vec::new();
+            for (property, value) in element.style_declarations() {
+                match property.as_str() {
+                    "font-weight"
+                        if value == "bold"
+                            || value == "bolder"
+                            || value.parse::<u32>().is_ok_and(|weight| weight >= 600) =>
+                    {
+                        emphases.push(Emphasis::Strong)
+                    }
+                    "font-style" if value == "italic" || value == "oblique" => {
+                        emphases.push(Emphasis::Em)
+                    }
+                    "text-decoration" | "text-decoration-line"
+                        if value.split_whitespace().any(|v| v == "line-through") =>
+                    {
+                        emphases.push(Emphasis::Del)
+                    }
+                    _ => {}
+                }
+            }
+            emphases
+        }
+        _ => Vec::new(),
+    }
+}
+
+// The names of <meta> emitted in the frontmatter with Options::emit_frontmatter.
+const FRONTMATTER_META_NAMES: [&str; 3] = ["description", "author", "keywords"];
+
+// Renders an element in place of the built-in rendering of its tag name.
+pub type Handler<'a> = Rc<dyn Fn(&'a Element<'a>, &mut Renderer<'a>) -> Result<String> + 'a>;
+
 #[derive(Debug, PartialEq)]
 pub enum RenderError {
-    OutsideOfList,
+    InvalidThematicBreak(String),
+    TooDeep,
 }
 
 impl fmt::Display for RenderError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
-            RenderError::OutsideOfList => write!(f, "outside of list"),
+        match self {
+            RenderError::InvalidThematicBreak(rule) => {
+                write!(f, "invalid thematic break: {:?}", rule)
+            }
+            RenderError::TooDeep => write!(f, "elements are nested too deeply"),
         }
     }
 }
@@ -20,17 +81,18 @@ impl fmt::Display for RenderError {
 impl std::error::Error for RenderError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match *self {
-            RenderError::OutsideOfList => None,
+            RenderError::InvalidThematicBreak(_) => None,
+            RenderError::TooDeep => None,
         }
     }
 }
 
 struct ContextItem<'a> {
-    element: &'a Element,
+    element: &'a Element<'a>,
 }
 
 impl<'a> ContextItem<'a> {
-    fn new(element: &'a Element) -> Self {
+    fn new(element: &'a Element<'a>) -> Self {
         ContextItem { element }
     }
 }
@@ -45,7 +107,7 @@ impl<'a> Context<'a> {
         Context { items }
     }
 
-    fn push(&mut self, element: &'a Element) {
+    fn push(&mut self, element: &'a Element<'a>) {
         let item = ContextItem::new(element);
         self.items.push(item)
     }
@@ -54,24 +116,73 @@ impl<'a> Context<'a> {
         self.items.pop();
     }
 
-    fn get_last_list_tag(&mut self) -> Option<&str> {
+    // The marker and the depth of a list item are both taken from the nearest
+    // list ancestor, so mixed nesting like ul > li > ol uses the inner list.
+    fn get_last_list_element(&mut self) -> Option<&'a Element<'a>> {
         for item in self.items.iter().rev() {
-            let tag_name = &item.element.tag_name;
-            if tag_name == "ul" || tag_name == "ol" {
-                return Some(tag_name);
+            if item.element.is_list_element() {
+                return Some(item.element);
             }
         }
         None
     }
 
-    fn get_last_list_depth(&mut self) -> usize {
-        for item in self.items.iter().rev() {
-            let tag_name = &item.element.tag_name;
-            if tag_name == "ul" || tag_name == "ol" {
-                return item.element.list_depth();
-            }
+    // A list nested in other lists is indented one level for each of its
+    // ul/ol ancestors by the markers of their items, so the depth from CSS
+    // classes, like flat lists of Google Docs, adds only the levels beyond
+    // them.
+    fn get_last_list_depth(&mut self, class_prefix: &str) -> usize {
+        let ancestors = self
+            .items
+            .iter()
+            .filter(|item| item.element.is_list_element())
+            .count()
+            .saturating_sub(1);
+
+        match self.get_last_list_element() {
+            Some(element) => element.list_depth(class_prefix).saturating_sub(ancestors),
+            None => 0,
         }
-        0
+    }
+
+    // Whether the current element is nested in another element with the same
+    // emphasis, which would wrap the content twice like `****x****`.
+    fn is_nested_in_same_emphasis(&mut self, emphasis: Emphasis) -> bool {
+        self.items
+            .iter()
+            .rev()
+            .skip(1)
+            .any(|item| emphases(item.element).contains(&emphasis))
+    }
+
+    fn in_heading(&mut self) -> bool {
+        self.items.iter().rev().skip(1).any(|item| {
+            matches!(
+                item.element.tag_name.as_str(),
+                "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+            )
+        })
+    }
+
+    // Whether the current element directly follows a <br> sibling, ignoring
+    // whitespace between them.
+    fn follows_br(&mut self) -> bool {
+        let [.., parent, current] = self.items.as_slice() else {
+            return false;
+        };
+        let siblings = &parent.element.children;
+        let Some(index) = siblings
+            .iter()
+            .position(|node| as_element(node).is_some_and(|e| std::ptr::eq(e, current.element)))
+        else {
+            return false;
+        };
+        siblings[..index]
+            .iter()
+            .rev()
+            .find(|node| !matches!(node, Node::Text(text) if text.trim().is_empty()))
+            .and_then(as_element)
+            .is_some_and(|e| e.tag_name == "br")
     }
 
     fn prefer_one_liner(&mut self) -> bool {
@@ -87,99 +198,316 @@ impl<'a> Context<'a> {
 
 pub struct Renderer<'a> {
     ctx: Context<'a>,
-    root: &'a Node,
+    root: &'a Node<'a>,
+    options: &'a Options,
+    warnings: Vec<String>,
+    handlers: HashMap<String, Handler<'a>>,
 }
 
 impl<'a> Renderer<'a> {
-    pub fn new(root: &'a Node) -> Self {
+    pub fn new(root: &'a Node<'a>, options: &'a Options) -> Self {
         Self {
             ctx: Context::new(),
             root,
+            options,
+            warnings: Vec::new(),
+            handlers: HashMap::new(),
+        }
+    }
+
+    // Registers a handler consulted before the built-in rendering of elements
+    // with the tag name, like `callout` for `<callout>`. It can render the
+    // children of the element with `render_children`.
+    pub fn set_handler<F>(&mut self, tag_name: &str, handler: F)
+    where
+        F: Fn(&'a Element<'a>, &mut Renderer<'a>) -> Result<String> + 'a,
+    {
+        self.handlers
+            .insert(tag_name.to_ascii_lowercase(), Rc::new(handler));
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    fn warn(&mut self, message: String) {
+        self.warnings.push(message);
+    }
+
+    fn resolve_url(&self, url: &str) -> String {
+        match &self.options.base_url {
+            Some(base) => resolve_url(base, url),
+            None => url.to_string(),
         }
     }
 
     pub fn render(&mut self) -> Result<String> {
-        let mut result = self.render_node(self.root)?;
-        if !result.ends_with('\n') {
-            result.push('\n');
+        let content = self.render_node(self.root)?;
+        let mut result = Self::collapse_blank_lines(&Self::trim_trailing_whitespaces(&content))
+            .trim_start_matches('\n')
+            .to_string();
+        if self.options.emit_frontmatter {
+            let frontmatter = self.render_frontmatter();
+            if !frontmatter.is_empty() {
+                result = format!("{}\n{}", frontmatter, result);
+            }
+        }
+        match self.options.trailing_newline {
+            TrailingNewline::None => {
+                result.truncate(result.trim_end_matches('\n').len());
+            }
+            TrailingNewline::Single => {
+                result.truncate(result.trim_end_matches('\n').len());
+                result.push('\n');
+            }
+            TrailingNewline::Preserve => {}
         }
         Ok(result)
     }
 
-    fn render_node(&mut self, node: &'a Node) -> Result<String> {
+    // The <title> and the <meta> of FRONTMATTER_META_NAMES in <head> are
+    // emitted as YAML in that order, quoting values as double-quoted strings.
+    fn render_frontmatter(&self) -> String {
+        let Some(head) = as_element(self.root).and_then(|html| {
+            html.children
+                .iter()
+                .filter_map(as_element)
+                .find(|e| e.tag_name == "head")
+        }) else {
+            return String::new();
+        };
+
+        let mut entries: Vec<(&str, String)> = Vec::new();
+        if let Some(title) = head
+            .children
+            .iter()
+            .filter_map(as_element)
+            .find(|e| e.tag_name == "title")
+        {
+            let mut text = String::new();
+            for child in &title.children {
+                if let Node::Text(content) = child {
+                    text.push_str(content);
+                }
+            }
+            entries.push(("title", decode_code_text(&text)));
+        }
+        for name in FRONTMATTER_META_NAMES {
+            let content = head.children.iter().filter_map(as_element).find_map(|e| {
+                let matches = e.tag_name == "meta"
+                    && e.attributes
+                        .get("name")
+                        .is_some_and(|n| n.eq_ignore_ascii_case(name));
+                if matches {
+                    e.attributes.get("content")
+                } else {
+                    None
+                }
+            });
+            if let Some(content) = content {
+                entries.push((name, decode_code_text(content)));
+            }
+        }
+
+        let entries: Vec<(&str, String)> = entries
+            .into_iter()
+            .map(|(key, value)| {
+                (
+                    key,
+                    value.split_whitespace().collect::<Vec<&str>>().join(" "),
+                )
+            })
+            .filter(|(_, value)| !value.is_empty())
+            .collect();
+        if entries.is_empty() {
+            return String::new();
+        }
+
+        let mut result = String::from("---\n");
+        for (key, value) in entries {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            result.push_str(&format!("{}: \"{}\"\n", key, value));
+        }
+        result.push_str("---\n");
+        result
+    }
+
+    // Trailing whitespaces are removed from each line except for exactly two
+    // spaces after some content, which is a hard line break.
+    fn trim_trailing_whitespaces(content: &str) -> String {
+        let mut lines = Vec::new();
+        for (line, fenced) in Self::fenced_lines(content) {
+            let trimmed = line.trim_end();
+            let trailing = &line[trimmed.len()..];
+            if fenced || (!trimmed.is_empty() && trailing == "  ") {
+                lines.push(line);
+            } else {
+                lines.push(trimmed);
+            }
+        }
+        lines.join("\n")
+    }
+
+    // Successive blank lines are collapsed into one except in fenced code
+    // blocks.
+    fn collapse_blank_lines(content: &str) -> String {
+        let mut lines = Vec::new();
+        let mut previous_blank = false;
+
+        for (line, fenced) in Self::fenced_lines(content) {
+            let blank = line.trim().is_empty();
+            if !fenced && blank && previous_blank {
+                continue;
+            }
+            previous_blank = !fenced && blank;
+            lines.push(line);
+        }
+
+        lines.join("\n")
+    }
+
+    // Splits the content into lines, paired with whether each of them is
+    // inside a fenced code block. The fences themselves are not inside.
+    // A fence is closed only by a fence of the same character which is at
+    // least as long as the opening one, so that a longer fence can contain
+    // a shorter one.
+    fn fenced_lines(content: &str) -> Vec<(&str, bool)> {
+        let mut lines = Vec::new();
+        let mut fence: Option<(char, usize)> = None;
+
+        for line in content.split('\n') {
+            let opening = Self::fence_of(line.trim_start());
+            match (fence, opening) {
+                (Some((c, len)), Some((d, n))) if c == d && n >= len => {
+                    if line.trim_start()[n..].trim().is_empty() {
+                        fence = None;
+                        lines.push((line, false));
+                    } else {
+                        lines.push((line, true));
+                    }
+                }
+                (Some(_), _) => lines.push((line, true)),
+                (None, _) => {
+                    fence = opening;
+                    lines.push((line, false));
+                }
+            }
+        }
+
+        lines
+    }
+
+    // Returns the character and the length of the fence starting the line.
+    fn fence_of(line: &str) -> Option<(char, usize)> {
+        let c = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+        let len = line.len() - line.trim_start_matches(c).len();
+        if len >= 3 {
+            Some((c, len))
+        } else {
+            None
+        }
+    }
+
+    fn render_node(&mut self, node: &'a Node<'a>) -> Result<String> {
+        let mut result = String::new();
+        self.render_node_into(node, &mut result)?;
+        Ok(result)
+    }
+
+    // Appends the rendered node to the buffer so that children are rendered
+    // without intermediate strings.
+    fn render_node_into(&mut self, node: &'a Node<'a>, buffer: &mut String) -> Result<()> {
         match node {
             Node::Element(element) => {
+                if self.ctx.items.len() >= self.options.max_depth.unwrap_or(DEFAULT_MAX_DEPTH) {
+                    return Err(RenderError::TooDeep);
+                }
                 self.ctx.push(element);
                 let result = self.render_element(element);
                 self.ctx.pop();
-                result
+                buffer.push_str(&result?);
             }
-            Node::Text(content) => self.render_text(content),
+            Node::Text(content) => buffer.push_str(&self.render_text(content)?),
         }
+        Ok(())
     }
 
-    fn render_element(&mut self, element: &'a Element) -> Result<String> {
+    fn render_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        if let Some(handler) = self.handlers.get(&element.tag_name).cloned() {
+            return handler(element, self);
+        }
+
         match element.tag_name.as_str() {
             "a" => self.render_a_element(element),
             "abbr" => self.render_children(element),
-            "address" => self.render_children(element),
-            "article" => self.render_children(element),
-            "aside" => self.render_children(element),
+            "address" => self.render_address_element(element),
+            "audio" => self.render_media_element(element),
+            "article" => self.render_sectioning_element(element),
+            "aside" => self.render_sectioning_element(element),
             "b" => self.render_children(element),
             "bdi" => self.render_children(element),
-            "bdo" => self.render_children(element),
+            "bdo" => self.render_bdo_element(element),
             "blockquote" => self.render_blockquote_element(element),
             "body" => self.render_container_element(element),
             "br" => self.render_br_element(element),
             "cite" => self.render_children(element),
             "code" => self.render_code_element(element),
-            "data" => self.render_children(element),
-            "dd" => self.render_children(element),
+            "data" => self.render_data_element(element),
+            "dd" => self.render_dd_element(element),
             "del" => self.render_del_element(element),
-            "details" => self.render_children(element),
+            "details" => self.render_details_element(element),
             "dfn" => self.render_children(element),
             "div" => self.render_container_element(element),
-            "dl" => self.render_children(element),
+            "dl" => self.render_dl_element(element),
             "dt" => self.render_dt_element(element),
             "em" => self.render_em_element(element),
-            "h1" => self.render_h1_element(element),
-            "h2" => self.render_h2_element(element),
-            "h3" => self.render_h3_element(element),
-            "h4" => self.render_h4_element(element),
-            "h5" => self.render_h5_element(element),
-            "h6" => self.render_h6_element(element),
+            "figcaption" => self.render_figcaption_element(element),
+            "figure" => self.render_figure_element(element),
+            "footer" => self.render_sectioning_element(element),
+            "h1" => self.render_heading_element(element, 1),
+            "h2" => self.render_heading_element(element, 2),
+            "h3" => self.render_heading_element(element, 3),
+            "h4" => self.render_heading_element(element, 4),
+            "h5" => self.render_heading_element(element, 5),
+            "h6" => self.render_heading_element(element, 6),
             "hr" => self.render_hr_element(element),
+            "header" => self.render_sectioning_element(element),
             "html" => self.render_html_element(element),
             "i" => self.render_children(element),
-            "img" => self.render_element_in_html_form(element),
-            "ins" => self.render_children(element),
+            "img" => self.render_img_element(element),
+            "ins" => self.render_ins_element(element),
             "kbd" => self.render_children(element),
             "li" => self.render_li_element(element),
-            "main" => self.render_children(element),
+            "main" => self.render_sectioning_element(element),
             "mark" => self.render_children(element),
-            "menu" => self.render_children(element),
-            "nav" => self.render_children(element),
+            "menu" => self.render_stacked_children(element),
+            "nav" => self.render_sectioning_element(element),
+            "noscript" => self.render_noscript_element(element),
             "ol" => self.render_stacked_children(element),
             "p" => self.render_p_element(element),
-            "pre" => self.render_children(element),
-            "q" => self.render_children(element),
+            "picture" => self.render_picture_element(element),
+            "pre" => self.render_pre_element(element),
+            "q" => self.render_q_element(element),
             "rp" => self.render_nothing(element),
             "rt" => self.render_nothing(element),
-            "ruby" => self.render_children(element),
+            "ruby" => self.render_ruby_element(element),
             "s" => self.render_children(element),
             "samp" => self.render_children(element),
-            "section" => self.render_children(element),
-            "small" => self.render_children(element),
-            "span" => self.render_children(element),
+            "section" => self.render_sectioning_element(element),
+            "small" => self.render_small_element(element),
+            "span" => self.render_span_element(element),
             "strong" => self.render_strong_element(element),
             "sub" => self.render_children(element),
             "summary" => self.render_children(element),
+            "template" => self.render_template_element(element),
+            "svg" => self.render_svg_element(element),
             "sup" => self.render_children(element),
-            "time" => self.render_children(element),
+            "time" => self.render_time_element(element),
             "u" => self.render_children(element),
             "ul" => self.render_stacked_children(element),
             "var" => self.render_children(element),
-            "wbr" => self.render_children(element),
+            "video" => self.render_media_element(element),
+            "wbr" => self.render_wbr_element(element),
 
             // table
             "table" => self.render_table_element(element),
@@ -189,16 +517,16 @@ impl<'a> Renderer<'a> {
             "th" => self.render_th_element(element),
             "td" => self.render_td_element(element),
             "caption" | "colgroup" | "col" | "tfoot" => self.render_nothing(element),
+            "html2md:html-table" => self.render_html_table_element(element),
 
             // successive lists
             "html2md:successive-lists-wrapper" => self.render_stacked_children(element),
 
             // render nothing
-            "area" | "audio" | "button" | "canvas" | "datalist" | "dialog" | "embed"
-            | "fieldset" | "figcaption" | "figure" | "footer" | "form" | "header" | "hgroup"
-            | "iframe" | "input" | "label" | "legend" | "map" | "meter" | "noscript" | "object"
-            | "optgroup" | "option" | "output" | "picture" | "progress" | "script" | "search"
-            | "select" | "slot" | "source" | "template" | "textarea" | "track" | "video" => {
+            "area" | "button" | "canvas" | "datalist" | "dialog" | "embed" | "fieldset"
+            | "form" | "hgroup" | "iframe" | "input" | "label" | "legend" | "map" | "meter"
+            | "object" | "optgroup" | "option" | "output" | "progress" | "script" | "search"
+            | "select" | "slot" | "source" | "style" | "textarea" | "track" => {
                 self.render_nothing(element)
             }
 
@@ -207,80 +535,146 @@ impl<'a> Renderer<'a> {
         }
     }
 
-    fn render_children(&mut self, element: &'a Element) -> Result<String> {
+    pub fn render_children(&mut self, element: &'a Element<'a>) -> Result<String> {
         let mut result = String::new();
 
         for child in &element.children {
-            let content = self.render_node(child)?;
-            result.push_str(&content);
+            self.render_node_into(child, &mut result)?;
         }
 
         Ok(result)
     }
 
-    fn render_stacked_children(&mut self, element: &'a Element) -> Result<String> {
-        let mut parts = Vec::new();
+    fn render_stacked_children(&mut self, element: &'a Element<'a>) -> Result<String> {
+        let mut result = String::new();
 
-        for node in &element.children {
-            let content = self.render_node(node)?;
-            parts.push(content);
+        for (i, node) in element.children.iter().enumerate() {
+            if i > 0 {
+                result.push('\n');
+            }
+            self.render_node_into(node, &mut result)?;
         }
 
-        Ok(parts.join("\n"))
+        Ok(result)
     }
 
-    fn render_container_element(&mut self, element: &'a Element) -> Result<String> {
-        let mut parts = Vec::new();
-        let mut part = String::new();
-
-        for node in &element.children {
-            let content = self.render_node(node)?;
+    // Inline content is gathered into parts and they are separated with blank
+    // lines at block elements, either before or after them.
+    fn render_container_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        self.render_container_nodes(element.children.iter())
+    }
 
-            match node {
-                Node::Element(child) => {
-                    if is_block_element(&child.tag_name) && !part.is_empty() {
-                        parts.push(part);
-                        part = String::new();
-                    }
-                    part.push_str(&content);
-                }
-                Node::Text(_) => {
-                    part.push_str(&content);
+    fn render_container_nodes<I: Iterator<Item = &'a Node<'a>>>(
+        &mut self,
+        nodes: I,
+    ) -> Result<String> {
+        let mut result = String::new();
+        let mut part_start = 0;
+        let mut after_block = false;
+
+        for node in nodes {
+            let is_block =
+                matches!(node, Node::Element(child) if is_block_element(&child.tag_name));
+            // A part of only whitespace, like a leading <br>, is dropped so that
+            // it leaves no blank lines before the block.
+            if is_block {
+                if result[part_start..].trim().is_empty() {
+                    result.truncate(part_start);
+                } else {
+                    result.push_str("\n\n");
+                    part_start = result.len();
                 }
             }
+            let node_start = result.len();
+            self.render_node_into(node, &mut result)?;
+
+            // Inline content following a block is put in a part of its own,
+            // like browsers wrap it in an anonymous block.
+            if is_block {
+                after_block = true;
+            } else if after_block && !result[node_start..].trim().is_empty() {
+                result.insert_str(node_start, "\n\n");
+                part_start = node_start + 2;
+                after_block = false;
+            }
         }
-        parts.push(part);
 
-        let result = parts.join("\n\n");
         Ok(result)
     }
 
+    // Sectioning elements are transparent, except that <header> and <footer>
+    // are dropped, unless their boundaries are annotated with HTML comments.
+    fn render_sectioning_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        if !self.options.annotate_sections {
+            return match element.tag_name.as_str() {
+                "header" | "footer" => self.render_nothing(element),
+                _ => self.render_container_element(element),
+            };
+        }
+
+        let content = self.render_container_element(element)?;
+        Ok(format!(
+            "<!-- {} -->\n\n{}\n\n<!-- /{} -->",
+            element.tag_name, content, element.tag_name
+        ))
+    }
+
     fn render_nothing(&mut self, _: &Element) -> Result<String> {
         Ok(String::new())
     }
 
-    fn render_unsupported_element(&mut self, element: &'a Element) -> Result<String> {
-        eprintln!(
-            "`{}` element is not supported. rendering nothing.",
-            element.tag_name
-        );
-        self.render_nothing(element)
+    fn render_unsupported_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        match self.options.unknown_element {
+            UnknownElement::Drop => {
+                self.warn(format!(
+                    "`{}` element is not supported. rendering nothing.",
+                    element.tag_name
+                ));
+                self.render_nothing(element)
+            }
+            UnknownElement::Unwrap => {
+                self.warn(format!(
+                    "`{}` element is not supported. rendering its children.",
+                    element.tag_name
+                ));
+                self.render_children(element)
+            }
+            UnknownElement::RawHtml => {
+                self.warn(format!(
+                    "`{}` element is not supported. rendering it in HTML form.",
+                    element.tag_name
+                ));
+                if Self::has_block_child(element) {
+                    let open_tag = self.render_open_tag(element);
+                    self.render_html_block(element, open_tag)
+                } else {
+                    self.render_element_in_html_form(element)
+                }
+            }
+        }
     }
 
-    fn render_element_in_html_form(&mut self, element: &'a Element) -> Result<String> {
-        let mut open_tag = String::new();
-        open_tag.push('<');
-        open_tag.push_str(&element.tag_name);
-        if !element.attributes.is_empty() {
-            let mut names: Vec<&String> = element.attributes.keys().collect();
-            names.sort();
+    fn has_block_child(element: &Element) -> bool {
+        element.children.iter().any(|node| match node {
+            Node::Element(child) => is_block_element(&child.tag_name),
+            Node::Text(_) => false,
+        })
+    }
 
-            for name in names {
-                let value = element.attributes.get(name).unwrap();
-                open_tag.push_str(&format!(" {}=\"{}\"", name, value));
-            }
-        }
-        open_tag.push('>');
+    // The tags are put on their own lines and surrounded by blank lines to make
+    // an HTML block, and the content is separated by blank lines so that it is
+    // still parsed as Markdown.
+    fn render_html_block(&mut self, element: &'a Element<'a>, open_tag: String) -> Result<String> {
+        let close_tag = format!("</{}>", &element.tag_name);
+        let content = self.render_container_element(element)?;
+        Ok(format!(
+            "\n\n{}\n\n{}\n\n{}\n\n",
+            open_tag, content, close_tag
+        ))
+    }
+
+    fn render_element_in_html_form(&mut self, element: &'a Element<'a>) -> Result<String> {
+        let open_tag = self.render_open_tag(element);
 
         if is_void_element(&element.tag_name) {
             return Ok(open_tag);
@@ -292,6 +686,14 @@ impl<'a> Renderer<'a> {
         Self::wrap(&content, &open_tag, &close_tag)
     }
 
+    fn render_open_tag(&self, element: &Element) -> String {
+        open_tag(element, self.options)
+    }
+
+    fn render_node_in_html_form(&self, node: &Node) -> String {
+        serialize_html_with_options(node, self.options)
+    }
+
     fn wrap(content: &str, prefix: &str, suffix: &str) -> Result<String> {
         let mut result = String::new();
         result.push_str(prefix);
@@ -300,117 +702,571 @@ impl<'a> Renderer<'a> {
         Ok(result)
     }
 
-    fn render_a_element(&mut self, element: &'a Element) -> Result<String> {
-        let content = self.render_children(element)?;
+    // Emphasis markers must be adjacent to non-whitespace characters, so
+    // whitespace at the edges of the content is moved outside of them, and no
+    // markers are emitted for empty content.
+    fn wrap_inline(content: &str, prefix: &str, suffix: &str) -> Result<String> {
+        let trimmed = content.trim();
+        if trimmed.is_empty() {
+            return Ok(content.to_string());
+        }
 
+        let leading = &content[..(content.len() - content.trim_start().len())];
+        let trailing = &content[(content.trim_end().len())..];
+
+        let mut result = String::new();
+        result.push_str(leading);
+        result.push_str(prefix);
+        result.push_str(trimmed);
+        result.push_str(suffix);
+        result.push_str(trailing);
+        Ok(result)
+    }
+
+    // A named anchor is kept in HTML form with all of its attributes, a link
+    // with href is rendered as a Markdown link with its title if any, and
+    // otherwise only the content is rendered.
+    fn render_a_element(&mut self, element: &'a Element<'a>) -> Result<String> {
         if element.attributes.contains_key("name") {
-            self.render_element_in_html_form(element)
-        } else if let Some(href) = element.attributes.get("href") {
-            Ok(format!("[{}]({})", content, href))
+            return self.render_element_in_html_form(element);
+        }
+        let Some(href) = element.attributes.get("href") else {
+            return self.render_children(element);
+        };
+        let href = self.resolve_url(href);
+        let destination = match element.attributes.get("title") {
+            Some(title) => format!("{} \"{}\"", href, title.replace('"', "\\\"")),
+            None => href.clone(),
+        };
+
+        // Link text can't span blocks, so a link wrapping blocks is kept as an
+        // HTML block.
+        if Self::has_block_child(element) {
+            let mut tag = Element::new(&element.tag_name, &element.attributes);
+            tag.attributes.insert(String::from("href"), href);
+            let open_tag = self.render_open_tag(&tag);
+            return self.render_html_block(element, open_tag);
+        }
+
+        // Whitespace around the image, like indentation of the source, doesn't
+        // make it a part of the link text.
+        let significant: Vec<&Node> = element
+            .children
+            .iter()
+            .filter(|node| !matches!(node, Node::Text(text) if text.trim().is_empty()))
+            .collect();
+        if let [Node::Element(img)] = significant.as_slice() {
+            if img.tag_name == "img" {
+                if let Some(src) = img.attributes.get("src") {
+                    let alt = img.attributes.get("alt").map_or("", |alt| alt.as_str());
+                    let src = self.resolve_url(src);
+                    return Ok(format!(
+                        "[![{}]({})]({})",
+                        Self::escape_link_text(alt),
+                        Self::link_destination(&src),
+                        destination
+                    ));
+                }
+            }
+        }
+
+        // Whitespace at the edges of the link text is moved outside of it like
+        // emphasis markers.
+        let content = self.render_children(element)?;
+        let trimmed = content.trim();
+        if trimmed.is_empty() {
+            return Ok(format!("[{}]({})", content, destination));
+        }
+        let leading = &content[..content.len() - content.trim_start().len()];
+        let trailing = &content[content.trim_end().len()..];
+        if self.options.autolinks && trimmed == destination && Self::is_autolinkable(&href) {
+            Ok(format!("{}<{}>{}", leading, href, trailing))
         } else {
-            Ok(content)
+            Ok(format!(
+                "{}[{}]({}){}",
+                leading, trimmed, destination, trailing
+            ))
         }
     }
 
-    fn render_blockquote_element(&mut self, element: &'a Element) -> Result<String> {
+    // Brackets and backslashes would end or escape the text of a link.
+    fn escape_link_text(text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        for c in text.chars() {
+            if matches!(c, '[' | ']' | '\\') {
+                result.push('\\');
+            }
+            result.push(c);
+        }
+        result
+    }
+
+    // A destination with spaces or parentheses is put in angle brackets, in
+    // which `<` and `>` are escaped.
+    fn link_destination(url: &str) -> String {
+        if url.contains(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '<' | '>')) {
+            format!("<{}>", url.replace('<', "\\<").replace('>', "\\>"))
+        } else {
+            url.to_string()
+        }
+    }
+
+    // An autolink is an absolute URL without spaces and angle brackets.
+    fn is_autolinkable(url: &str) -> bool {
+        has_scheme(url) && !url.contains(|c: char| c.is_whitespace() || c == '<' || c == '>')
+    }
+
+    fn render_address_element(&mut self, element: &'a Element<'a>) -> Result<String> {
         let content = self.render_container_element(element)?;
 
+        match self.options.address {
+            Address::Plain => Ok(content),
+            Address::Italic => Ok(Self::emphasize_lines(&content)),
+            Address::Blockquote => Ok(Self::quote(&content)),
+        }
+    }
+
+    fn emphasize_lines(content: &str) -> String {
+        let mut parts = Vec::new();
+        for line in content.lines() {
+            if line.trim().is_empty() {
+                parts.push(line.to_string());
+            } else {
+                parts.push(format!("_{}_", line));
+            }
+        }
+        parts.join("\n")
+    }
+
+    fn render_blockquote_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        // A trailing <footer> child is moved to an attribution line, and so are
+        // a trailing <cite> child and the cite attribute in the attribution
+        // mode.
+        let attributed = self.options.blockquote_attribution;
+        let source = match element.children.last() {
+            Some(Node::Element(last))
+                if last.tag_name == "footer" || (attributed && last.tag_name == "cite") =>
+            {
+                Some(last)
+            }
+            _ => None,
+        };
+        let body_len = element.children.len() - usize::from(source.is_some());
+        let mut content = self.render_container_nodes(element.children[..body_len].iter())?;
+
+        let attribution = match (source, element.attributes.get("cite")) {
+            (Some(source), _) => {
+                let text = self.render_container_element(source)?;
+                text.split_whitespace().collect::<Vec<_>>().join(" ")
+            }
+            (None, Some(cite)) if attributed => self.resolve_url(cite),
+            _ => String::new(),
+        };
+        if !attribution.is_empty() {
+            if !content.is_empty() {
+                content.push_str("\n\n");
+            }
+            content.push_str("— ");
+            content.push_str(&attribution);
+        }
+        Ok(Self::quote(&content))
+    }
+
+    fn quote(content: &str) -> String {
         let mut parts = Vec::new();
         for line in content.lines() {
             parts.push(format!("> {}", line));
         }
-        Ok(parts.join("\n"))
+        parts.join("\n")
     }
 
-    fn render_br_element(&mut self, element: &'a Element) -> Result<String> {
+    // A heading and a table cell are both a single line, so a line break
+    // becomes a space in the former and stays in HTML form in the latter.
+    fn render_br_element(&mut self, element: &'a Element<'a>) -> Result<String> {
         if self.ctx.prefer_one_liner() {
             self.render_element_in_html_form(element)
+        } else if self.ctx.in_heading() {
+            if self.ctx.follows_br() {
+                Ok(String::new())
+            } else {
+                Ok(String::from(" "))
+            }
         } else {
             Ok(String::from("\n"))
         }
     }
 
-    fn render_code_element(&mut self, element: &'a Element) -> Result<String> {
+    fn render_code_element(&mut self, element: &'a Element<'a>) -> Result<String> {
         let content = self.render_children(element)?;
-        Self::wrap(&content, "`", "`")
+        if content.trim().is_empty() {
+            return Ok(content);
+        }
+
+        // A code span is delimited with a backtick string longer than any
+        // backtick run in its content, and padded when the content begins or
+        // ends with a backtick.
+        let mut longest_run = 0;
+        let mut run = 0;
+        for c in content.chars() {
+            if c == '`' {
+                run += 1;
+                longest_run = longest_run.max(run);
+            } else {
+                run = 0;
+            }
+        }
+        let delimiter = "`".repeat(longest_run + 1);
+
+        if content.starts_with('`') || content.ends_with('`') {
+            Self::wrap(
+                &content,
+                &format!("{} ", delimiter),
+                &format!(" {}", delimiter),
+            )
+        } else {
+            Self::wrap(&content, &delimiter, &delimiter)
+        }
     }
 
-    fn render_del_element(&mut self, element: &'a Element) -> Result<String> {
+    fn render_del_element(&mut self, element: &'a Element<'a>) -> Result<String> {
         let content = self.render_children(element)?;
-        Self::wrap(&content, "~", "~")
+        if self.ctx.is_nested_in_same_emphasis(Emphasis::Del) {
+            return Ok(content);
+        }
+        self.strikethrough(&content)
     }
 
-    fn render_dt_element(&mut self, element: &'a Element) -> Result<String> {
-        self.render_children(element)
+    // Strikethrough is an extension, which CommonMark keeps in HTML form.
+    fn strikethrough(&self, content: &str) -> Result<String> {
+        match self.options.flavor {
+            Flavor::Gfm => Self::wrap_inline(content, "~", "~"),
+            Flavor::CommonMark => Self::wrap_inline(content, "<del>", "</del>"),
+            Flavor::Pandoc => Self::wrap_inline(content, "~~", "~~"),
+        }
     }
 
-    fn render_em_element(&mut self, element: &'a Element) -> Result<String> {
-        let content = self.render_children(element)?;
-        Self::wrap(&content, "_", "_")
+    fn render_data_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        if self.options.data_html {
+            self.render_element_in_html_form(element)
+        } else {
+            self.render_children(element)
+        }
     }
 
-    fn render_h1_element(&mut self, element: &'a Element) -> Result<String> {
-        let content = self.render_children(element)?;
-        Self::wrap(&content, "# ", "")
+    fn render_details_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        match self.options.details {
+            Details::Unwrap => self.render_container_element(element),
+            Details::Html => {
+                let (summary, body) = self.render_summary_and_body(element)?;
+                let summary = match summary {
+                    Some(summary) => format!("\n<summary>{}</summary>", summary),
+                    None => String::new(),
+                };
+                Ok(format!(
+                    "\n\n{}{}\n\n{}\n\n</details>\n\n",
+                    self.render_open_tag(element),
+                    summary,
+                    body
+                ))
+            }
+            Details::Markdown => {
+                let (summary, body) = self.render_summary_and_body(element)?;
+                match summary {
+                    Some(summary) => {
+                        Ok(format!("\n\n**{}**\n\n{}\n\n", summary, Self::quote(&body)))
+                    }
+                    None => Ok(format!("\n\n{}\n\n", Self::quote(&body))),
+                }
+            }
+            Details::Heading => {
+                let (summary, body) = self.render_summary_and_body(element)?;
+                let summary = summary
+                    .unwrap_or_default()
+                    .lines()
+                    .map(|line| line.trim())
+                    .filter(|line| !line.is_empty())
+                    .collect::<Vec<&str>>()
+                    .join(" ");
+                if summary.is_empty() {
+                    Ok(format!("\n\n{}\n\n", body))
+                } else {
+                    Ok(format!("\n\n### {}\n\n{}\n\n", summary, body))
+                }
+            }
+        }
     }
 
-    fn render_h2_element(&mut self, element: &'a Element) -> Result<String> {
-        let content = self.render_children(element)?;
-        Self::wrap(&content, "## ", "")
+    // The body of <details> is everything but its <summary>.
+    fn render_summary_and_body(
+        &mut self,
+        element: &'a Element<'a>,
+    ) -> Result<(Option<String>, String)> {
+        let is_summary = |node: &Node| matches!(node, Node::Element(e) if e.tag_name == "summary");
+        let summary = match element.children.iter().find(|node| is_summary(node)) {
+            Some(node) => Some(self.render_node(node)?),
+            None => None,
+        };
+        let body =
+            self.render_container_nodes(element.children.iter().filter(|node| !is_summary(node)))?;
+        Ok((summary, body))
     }
 
-    fn render_h3_element(&mut self, element: &'a Element) -> Result<String> {
-        let content = self.render_children(element)?;
-        Self::wrap(&content, "### ", "")
+    fn render_dd_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        let content = self.render_container_element(element)?;
+        Ok(Self::prepend_list_marker(":", &content))
     }
 
-    fn render_h4_element(&mut self, element: &'a Element) -> Result<String> {
-        let content = self.render_children(element)?;
-        Self::wrap(&content, "#### ", "")
+    // Each term and definition is put on its own line, or joined with <br> in
+    // a table cell.
+    fn render_dl_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        if self.options.flavor == Flavor::CommonMark {
+            return Ok(self.render_node_in_html_form(&Node::Element(element.clone())));
+        }
+
+        let content = self.render_stacked_children(element)?;
+        if self.ctx.prefer_one_liner() {
+            Ok(content.lines().collect::<Vec<&str>>().join("<br>"))
+        } else {
+            Ok(content)
+        }
     }
 
-    fn render_h5_element(&mut self, element: &'a Element) -> Result<String> {
-        let content = self.render_children(element)?;
-        Self::wrap(&content, "##### ", "")
+    fn render_dt_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        self.render_children(element)
     }
 
-    fn render_h6_element(&mut self, element: &'a Element) -> Result<String> {
+    fn render_em_element(&mut self, element: &'a Element<'a>) -> Result<String> {
         let content = self.render_children(element)?;
-        Self::wrap(&content, "###### ", "")
+        if self.ctx.is_nested_in_same_emphasis(Emphasis::Em) {
+            return Ok(content);
+        }
+        Self::wrap_inline(&content, "_", "_")
+    }
+
+    fn render_figcaption_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        let content = self.render_container_element(element)?;
+        Ok(Self::emphasize_lines(&content))
+    }
+
+    // The caption is put on the line immediately after the figure content.
+    // The content and the caption are separated by a blank line, since a line
+    // of only HTML like `<img>` starts an HTML block and would swallow the
+    // caption.
+    fn render_figure_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        let mut parts = Vec::new();
+        for node in &element.children {
+            let part = self.render_node(node)?;
+            if !part.trim().is_empty() {
+                parts.push(part.trim_matches('\n').to_string());
+            }
+        }
+        Ok(parts.join("\n\n"))
+    }
+
+    fn render_heading_element(&mut self, element: &'a Element<'a>, level: usize) -> Result<String> {
+        // A heading is a single line, so line breaks of block children inside it
+        // become spaces.
+        let mut content = self
+            .render_children(element)?
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<&str>>()
+            .join(" ");
+        if let Some(id) = element.attributes.get("id") {
+            if self.options.heading_ids {
+                content.push_str(&format!(" {{#{}}}", id));
+            }
+        }
+
+        // Setext headings exist only for level 1 and 2 and need some text.
+        let underline = match level {
+            1 => "=",
+            2 => "-",
+            _ => "",
+        };
+        if self.options.heading_style == HeadingStyle::Setext
+            && !underline.is_empty()
+            && !content.trim().is_empty()
+        {
+            let width = content.chars().count();
+            Self::wrap(&content, "", &format!("\n{}", underline.repeat(width)))
+        } else {
+            Self::wrap(&content, &format!("{} ", "#".repeat(level)), "")
+        }
     }
 
     fn render_hr_element(&mut self, _: &Element) -> Result<String> {
-        Ok(String::from("---"))
+        match &self.options.thematic_break {
+            None => Ok(String::from("---")),
+            Some(rule) if is_thematic_break(rule) => Ok(rule.clone()),
+            Some(rule) => Err(RenderError::InvalidThematicBreak(rule.clone())),
+        }
     }
 
-    fn render_html_element(&mut self, element: &'a Element) -> Result<String> {
+    fn render_html_element(&mut self, element: &'a Element<'a>) -> Result<String> {
         if let Some(body_node) = element.children.iter().find(|node| match node {
             Node::Element(e) => e.tag_name == "body",
             _ => false,
         }) {
             self.render_node(body_node)
         } else {
-            unreachable!()
+            self.render_nothing(element)
+        }
+    }
+
+    // Markdown has no way to override direction, so keep it in HTML form.
+    fn render_bdo_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        if element.attributes.contains_key("dir") {
+            self.render_element_in_html_form(element)
+        } else {
+            self.render_children(element)
+        }
+    }
+
+    fn render_img_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        match element.attributes.get("src") {
+            Some(src) if self.options.base_url.is_some() => {
+                let mut resolved = element.clone();
+                resolved
+                    .attributes
+                    .insert(String::from("src"), self.resolve_url(src));
+                Ok(self.render_open_tag(&resolved))
+            }
+            _ => self.render_element_in_html_form(element),
         }
     }
 
-    fn render_li_element(&mut self, element: &'a Element) -> Result<String> {
+    fn render_ins_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        // Nothing was inserted, like the other empty inline elements.
+        if element.children.is_empty() {
+            return Ok(String::new());
+        }
+
+        match self.options.ins {
+            Ins::PlusPlus if self.options.flavor != Flavor::CommonMark => {
+                let content = self.render_children(element)?;
+                Self::wrap_inline(&content, "++", "++")
+            }
+            _ => self.render_element_in_html_form(element),
+        }
+    }
+
+    fn render_li_element(&mut self, element: &'a Element<'a>) -> Result<String> {
         let mut result = String::new();
 
-        let marker = match self.ctx.get_last_list_tag() {
-            Some("ul") => "-",
-            Some("ol") => "1.",
-            _ => return Err(RenderError::OutsideOfList),
+        let marker = match self.ctx.get_last_list_element() {
+            Some(list) if list.tag_name == "ol" => {
+                if self.options.list_type_markers {
+                    Self::ordered_list_marker(list, element)
+                } else {
+                    String::from("1.")
+                }
+            }
+            // An item outside of any list is rendered like browsers do, as a
+            // bullet.
+            _ => String::from("-"),
         };
 
-        let content = self.render_container_element(element)?;
-        let marked_content = Self::prepend_list_marker(marker, &content);
-        let indented_content = Self::indent(&marked_content, self.ctx.get_last_list_depth());
+        let mut content = self.render_container_element(element)?;
+        if self.options.nested_list_on_new_line && Self::starts_with_list(element) {
+            content.insert(0, '\n');
+        }
+        let marked_content = Self::prepend_list_marker(&marker, &content);
+        let class_prefix = self
+            .options
+            .list_depth_class_prefix
+            .as_deref()
+            .unwrap_or(DEFAULT_LIST_DEPTH_CLASS_PREFIX);
+        let depth = self.ctx.get_last_list_depth(class_prefix);
+        let indented_content = Self::indent(&marked_content, depth);
         result.push_str(&indented_content);
 
         Ok(result)
     }
 
+    // The marker of an item of <ol type="a|A|i|I">, counting from the start
+    // attribute. Other types keep `1.` to let Markdown number the items.
+    fn ordered_list_marker(list: &Element, item: &Element) -> String {
+        let start = list
+            .attributes
+            .get("start")
+            .and_then(|start| start.trim().parse::<usize>().ok())
+            .unwrap_or(1);
+        let index = list
+            .children
+            .iter()
+            .filter_map(|node| match node {
+                Node::Element(e) if e.tag_name == "li" => Some(e),
+                _ => None,
+            })
+            .position(|li| std::ptr::eq(li, item))
+            .unwrap_or(0);
+        let number = start + index;
+
+        let marker = match list.attributes.get("type").map(|t| t.as_str()) {
+            Some("a") => Self::alphabetic_number(number),
+            Some("A") => Self::alphabetic_number(number).to_uppercase(),
+            Some("i") => Self::roman_number(number),
+            Some("I") => Self::roman_number(number).to_uppercase(),
+            _ => return String::from("1."),
+        };
+        format!("{}.", marker)
+    }
+
+    // 1 => a, 26 => z, 27 => aa, like browsers do.
+    fn alphabetic_number(number: usize) -> String {
+        if number == 0 {
+            return number.to_string();
+        }
+        let mut letters = Vec::new();
+        let mut n = number;
+        while n > 0 {
+            n -= 1;
+            letters.push((b'a' + (n % 26) as u8) as char);
+            n /= 26;
+        }
+        letters.iter().rev().collect()
+    }
+
+    fn roman_number(number: usize) -> String {
+        if number == 0 || number >= 4000 {
+            return number.to_string();
+        }
+        let mut result = String::new();
+        let mut n = number;
+        for (value, numeral) in [
+            (1000, "m"),
+            (900, "cm"),
+            (500, "d"),
+            (400, "cd"),
+            (100, "c"),
+            (90, "xc"),
+            (50, "l"),
+            (40, "xl"),
+            (10, "x"),
+            (9, "ix"),
+            (5, "v"),
+            (4, "iv"),
+            (1, "i"),
+        ] {
+            while n >= value {
+                result.push_str(numeral);
+                n -= value;
+            }
+        }
+        result
+    }
+
+    fn starts_with_list(element: &Element) -> bool {
+        match element.children.first() {
+            Some(Node::Element(child)) => {
+                child.is_list_element() || child.tag_name == "html2md:successive-lists-wrapper"
+            }
+            _ => false,
+        }
+    }
+
     fn prepend_list_marker(marker: &str, content: &str) -> String {
         let mut parts = Vec::new();
 
@@ -419,6 +1275,10 @@ impl<'a> Renderer<'a> {
             let mut part = String::new();
             if i == 0 {
                 part.push_str(marker);
+                if line.is_empty() {
+                    parts.push(part);
+                    continue;
+                }
             } else {
                 part.push_str(&sp);
             }
@@ -452,69 +1312,440 @@ impl<'a> Renderer<'a> {
         result
     }
 
-    fn render_p_element(&mut self, element: &'a Element) -> Result<String> {
+    // The URL is taken from the src attribute or the first <source> child, and
+    // the fallback content is used as the label if any.
+    fn render_media_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        let src = element.attributes.get("src").or_else(|| {
+            element.children.iter().find_map(|node| match node {
+                Node::Element(e) if e.tag_name == "source" => e.attributes.get("src"),
+                _ => None,
+            })
+        });
+        let Some(src) = src else {
+            return self.render_nothing(element);
+        };
+
+        let src = self.resolve_url(src);
+        let content = self.render_children(element)?;
+        let label = if content.trim().is_empty() {
+            src.as_str()
+        } else {
+            content.trim()
+        };
+        Ok(format!("[{}]({})", label, src))
+    }
+
+    fn render_p_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        if self.options.paragraph_alignment {
+            // Markdown wraps the content in its own <p>, which would close an
+            // aligned <p>, so the alignment is given to a <div> around it.
+            if let Some(align) = element.text_alignment() {
+                let content = self.render_container_element(element)?;
+                return Ok(format!(
+                    "\n\n<div align=\"{}\">\n\n{}\n\n</div>\n\n",
+                    align, content
+                ));
+            }
+        }
+
         let content = self.render_children(element)?;
         Self::wrap(&content, "", "")
     }
 
-    fn render_strong_element(&mut self, element: &'a Element) -> Result<String> {
+    // The text is put in a fenced code block as is, since markup can't be
+    // rendered inside it.
+    fn render_pre_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        let text: String = element
+            .children
+            .iter()
+            .map(|node| node.text_content())
+            .collect();
+        // A newline right after <pre> is not a part of the content.
+        let text = text.strip_prefix('\n').unwrap_or(&text);
+        let content = decode_code_text(text);
+        let content = content.strip_suffix('\n').unwrap_or(&content);
+
+        let mut longest_run = 0;
+        let mut run = 0;
+        for c in content.chars() {
+            if c == '`' {
+                run += 1;
+                longest_run = longest_run.max(run);
+            } else {
+                run = 0;
+            }
+        }
+        let fence = "`".repeat((longest_run + 1).max(3));
+
+        Ok(format!("{}\n{}\n{}", fence, content, fence))
+    }
+
+    // <source> elements are ignored and only the fallback <img> is rendered.
+    fn render_picture_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        let img_node = element.children.iter().find(|node| match node {
+            Node::Element(e) => e.tag_name == "img",
+            _ => false,
+        });
+        match img_node {
+            Some(node) => self.render_node(node),
+            None => self.render_nothing(element),
+        }
+    }
+
+    fn render_q_element(&mut self, element: &'a Element<'a>) -> Result<String> {
         let content = self.render_children(element)?;
-        Self::wrap(&content, "**", "**")
+        if content.trim().is_empty() {
+            return Ok(content);
+        }
+
+        match element
+            .attributes
+            .get("cite")
+            .filter(|_| self.options.q_citation)
+        {
+            Some(cite) => Ok(format!(
+                "\"{}\"[source]({})",
+                content,
+                self.resolve_url(cite)
+            )),
+            None => Ok(format!("\"{}\"", content)),
+        }
+    }
+
+    // Each <rt> annotates the base text since the previous one, and <rp> is
+    // only for browsers without ruby support.
+    fn render_ruby_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        match self.options.ruby {
+            Ruby::Base => self.render_children(element),
+            Ruby::Parentheses => {
+                let mut result = String::new();
+                for child in &element.children {
+                    match child {
+                        Node::Element(e) if e.tag_name == "rt" => {
+                            let reading = self.render_children(e)?;
+                            result.push_str(&format!("({})", reading));
+                        }
+                        Node::Element(e) if e.tag_name == "rp" => {}
+                        _ => self.render_node_into(child, &mut result)?,
+                    }
+                }
+                Ok(result)
+            }
+            Ruby::Html => Ok(self.render_node_in_html_form(&Node::Element(element.clone()))),
+        }
+    }
+
+    fn render_small_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        if self.options.small_html {
+            self.render_element_in_html_form(element)
+        } else {
+            self.render_children(element)
+        }
+    }
+
+    // The parser reads the contents of <noscript> as elements, unlike
+    // browsers running scripts which read them as text.
+    fn render_noscript_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        if self.options.render_noscript {
+            self.render_container_element(element)
+        } else {
+            self.render_nothing(element)
+        }
+    }
+
+    fn render_template_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        if self.options.template_contents {
+            self.render_container_element(element)
+        } else {
+            self.render_nothing(element)
+        }
     }
 
-    fn render_table_element(&mut self, element: &'a Element) -> Result<String> {
+    fn render_svg_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        if self.options.preserve_svg {
+            Ok(self.render_node_in_html_form(&Node::Element(element.clone())))
+        } else {
+            self.render_unsupported_element(element)
+        }
+    }
+
+    // Bold, italic and line-through given by the style attribute, like Google
+    // Docs emits, are rendered as strong, em and del, otherwise the span is
+    // unwrapped.
+    fn render_span_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        let mut content = self.render_children(element)?;
+
+        let emphases = emphases(element);
+        if emphases.contains(&Emphasis::Del) && !self.ctx.is_nested_in_same_emphasis(Emphasis::Del)
+        {
+            content = self.strikethrough(&content)?;
+        }
+        if emphases.contains(&Emphasis::Em) && !self.ctx.is_nested_in_same_emphasis(Emphasis::Em) {
+            content = Self::wrap_inline(&content, "_", "_")?;
+        }
+        if emphases.contains(&Emphasis::Strong)
+            && !self.ctx.is_nested_in_same_emphasis(Emphasis::Strong)
+        {
+            content = Self::wrap_inline(&content, "**", "**")?;
+        }
+        Ok(content)
+    }
+
+    fn render_strong_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        let content = self.render_children(element)?;
+        if self.ctx.is_nested_in_same_emphasis(Emphasis::Strong) {
+            return Ok(content);
+        }
+        Self::wrap_inline(&content, "**", "**")
+    }
+
+    fn render_time_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        let content = self.render_children(element)?;
+
+        match element.attributes.get("datetime") {
+            Some(datetime) if self.options.time_datetime => {
+                if content.is_empty() || content == *datetime {
+                    Ok(datetime.to_string())
+                } else {
+                    Ok(format!("{} ({})", content, datetime))
+                }
+            }
+            _ => Ok(content),
+        }
+    }
+
+    fn render_wbr_element(&mut self, _: &Element) -> Result<String> {
+        match self.options.wbr {
+            Wbr::Nothing => Ok(String::new()),
+            Wbr::ZeroWidthSpace => Ok(String::from("\u{200B}")),
+            Wbr::SoftHyphen => Ok(String::from("\u{00AD}")),
+        }
+    }
+
+    fn render_table_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        if self.options.flavor == Flavor::Pandoc {
+            return self.render_pandoc_table_element(element);
+        }
         self.render_stacked_children(element)
     }
 
-    fn render_thead_element(&mut self, element: &'a Element) -> Result<String> {
-        let mut parts = Vec::new();
+    // Pandoc aligns columns by the header cells, and a table whose cells have
+    // several lines is rendered as a grid table.
+    fn render_pandoc_table_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        let mut alignments = Vec::new();
+        for section in element.children.iter().filter_map(as_element) {
+            for tr in section.children.iter().filter_map(as_element) {
+                let mut cells = Vec::new();
+                for node in &tr.children {
+                    if let Node::Element(cell) = node {
+                        if rows.is_empty() {
+                            alignments.push(cell.text_alignment());
+                        }
+                        cells.push(self.render_node(node)?);
+                    }
+                }
+                rows.push(cells);
+            }
+        }
 
-        let tr = self.render_children(element)?;
-        parts.push(tr);
+        let columns = rows.iter().map(|cells| cells.len()).max().unwrap_or(0);
+        if columns == 0 {
+            return Ok(String::new());
+        }
+        for cells in rows.iter_mut() {
+            cells.resize(columns, String::new());
+        }
+        alignments.resize(columns, None);
 
-        let separator = self.render_table_separator_with_tr_node(&element.children[0])?;
-        parts.push(separator);
+        if rows.iter().flatten().any(|cell| cell.contains('\n')) {
+            Ok(Self::render_pandoc_grid_table(&rows, &alignments))
+        } else {
+            Ok(Self::render_pandoc_pipe_table(&rows, &alignments))
+        }
+    }
 
-        Ok(parts.join("\n"))
+    fn render_pandoc_pipe_table(rows: &[Vec<String>], alignments: &[Option<String>]) -> String {
+        let mut lines = Vec::new();
+        for (i, cells) in rows.iter().enumerate() {
+            lines.push(format!("| {} |", cells.join(" | ")));
+            if i == 0 {
+                lines.push(Self::render_table_separator(alignments));
+            }
+        }
+        lines.join("\n")
     }
 
-    fn render_table_separator_with_tr_node(&mut self, node: &Node) -> Result<String> {
-        let Node::Element(element) = node else {
-            unreachable!()
-        };
-        if element.tag_name != "tr" {
-            unreachable!()
+    fn render_pandoc_grid_table(rows: &[Vec<String>], alignments: &[Option<String>]) -> String {
+        let mut widths = vec![3; alignments.len()];
+        for cells in rows {
+            for (width, cell) in widths.iter_mut().zip(cells) {
+                for line in cell.lines() {
+                    *width = (*width).max(line.chars().count());
+                }
+            }
+        }
+
+        let border = |fill: &str, aligned: bool| {
+            let mut line = String::from("+");
+            for (width, align) in widths.iter().zip(alignments) {
+                let (left, right) = match align.as_deref() {
+                    Some("left") if aligned => (":", fill),
+                    Some("center") if aligned => (":", ":"),
+                    Some("right") if aligned => (fill, ":"),
+                    _ => (fill, fill),
+                };
+                line.push_str(left);
+                line.push_str(&fill.repeat(*width));
+                line.push_str(right);
+                line.push('+');
+            }
+            line
         };
 
+        let mut lines = vec![border("-", false)];
+        for (i, cells) in rows.iter().enumerate() {
+            let cell_lines: Vec<Vec<&str>> =
+                cells.iter().map(|cell| cell.lines().collect()).collect();
+            let height = cell_lines.iter().map(|l| l.len()).max().unwrap_or(0).max(1);
+            for n in 0..height {
+                let mut line = String::from("|");
+                for (width, cell) in widths.iter().zip(&cell_lines) {
+                    let text = cell.get(n).copied().unwrap_or("");
+                    let padding = width - text.chars().count();
+                    line.push_str(&format!(" {}{} |", text, " ".repeat(padding)));
+                }
+                lines.push(line);
+            }
+            lines.push(if i == 0 {
+                border("=", true)
+            } else {
+                border("-", false)
+            });
+        }
+        lines.join("\n")
+    }
+
+    // The wrapped table is not restructured, so it is emitted as it is.
+    fn render_html_table_element(&mut self, element: &'a Element<'a>) -> Result<String> {
         let mut result = String::new();
+        for child in &element.children {
+            result.push_str(&self.render_node_in_html_form(child));
+        }
+        Ok(result)
+    }
 
-        for _ in 0..element.children.len() {
-            result.push_str("|---");
+    // GFM has only one header row, so the cells of several rows are merged
+    // into one per column, repeating a cell spanning columns.
+    fn render_thead_element(&mut self, element: &'a Element<'a>) -> Result<String> {
+        let rows: Vec<&'a Element<'a>> = element
+            .children
+            .iter()
+            .filter_map(as_element)
+            .filter(|e| e.tag_name == "tr")
+            .collect();
+        match rows.as_slice() {
+            [] => Ok(String::new()),
+            [tr] => {
+                let header = self.render_children(element)?;
+                let alignments: Vec<Option<String>> = tr
+                    .children
+                    .iter()
+                    .map(|node| as_element(node).and_then(|cell| cell.text_alignment()))
+                    .collect();
+                let separator = Self::render_table_separator(&alignments);
+                Ok(format!("{}\n{}", header, separator))
+            }
+            _ => {
+                // Columns still spanned by a cell in a row above are skipped.
+                let mut columns: Vec<Vec<String>> = Vec::new();
+                let mut alignments: Vec<Option<String>> = Vec::new();
+                let mut pending_rowspans: Vec<usize> = Vec::new();
+                for tr in rows {
+                    let occupied: Vec<bool> = pending_rowspans.iter().map(|n| *n > 0).collect();
+                    for n in pending_rowspans.iter_mut() {
+                        *n = n.saturating_sub(1);
+                    }
+
+                    let mut column = 0;
+                    for node in &tr.children {
+                        let Node::Element(cell) = node else {
+                            continue;
+                        };
+                        while occupied.get(column) == Some(&true) {
+                            column += 1;
+                        }
+                        let content = Self::join_cell_lines(&self.render_node(node)?);
+                        for _ in 0..span_attribute(cell, "colspan") {
+                            if columns.len() <= column {
+                                columns.resize(column + 1, Vec::new());
+                                alignments.resize(column + 1, None);
+                                pending_rowspans.resize(column + 1, 0);
+                            }
+                            if !content.is_empty() {
+                                columns[column].push(content.clone());
+                            }
+                            if let Some(align) = cell.text_alignment() {
+                                alignments[column] = Some(align);
+                            }
+                            pending_rowspans[column] = span_attribute(cell, "rowspan") - 1;
+                            column += 1;
+                        }
+                    }
+                }
+                let cells: Vec<String> = columns.iter().map(|parts| parts.join("<br>")).collect();
+                let header = Self::wrap(&cells.join(" | "), "| ", " |")?;
+                let separator = Self::render_table_separator(&alignments);
+                Ok(format!("{}\n{}", header, separator))
+            }
+        }
+    }
+
+    fn render_table_separator(alignments: &[Option<String>]) -> String {
+        let mut result = String::new();
+
+        for align in alignments {
+            result.push_str(match align.as_deref() {
+                Some("left") => "|:---",
+                Some("center") => "|:---:",
+                Some("right") => "|---:",
+                _ => "|---",
+            });
         }
         result.push('|');
 
-        Ok(result)
+        result
     }
 
-    fn render_tbody_element(&mut self, element: &'a Element) -> Result<String> {
+    fn render_tbody_element(&mut self, element: &'a Element<'a>) -> Result<String> {
         self.render_stacked_children(element)
     }
 
-    fn render_tr_element(&mut self, element: &'a Element) -> Result<String> {
+    fn render_tr_element(&mut self, element: &'a Element<'a>) -> Result<String> {
         let mut cells = Vec::new();
         for child in &element.children {
             let cell = self.render_node(child)?;
-            cells.push(cell);
+            cells.push(Self::join_cell_lines(&cell));
         }
 
         Self::wrap(&cells.join(" | "), "| ", " |")
     }
 
-    fn render_th_element(&mut self, element: &'a Element) -> Result<String> {
+    // A row of a pipe table is one line, so lines of blocks in a cell, like
+    // paragraphs and list items, are joined with <br>.
+    fn join_cell_lines(content: &str) -> String {
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect::<Vec<&str>>()
+            .join("<br>")
+    }
+
+    fn render_th_element(&mut self, element: &'a Element<'a>) -> Result<String> {
         self.render_container_element(element)
     }
 
-    fn render_td_element(&mut self, element: &'a Element) -> Result<String> {
+    fn render_td_element(&mut self, element: &'a Element<'a>) -> Result<String> {
         self.render_container_element(element)
     }
 
@@ -523,51 +1754,93 @@ impl<'a> Renderer<'a> {
     }
 }
 
-fn decode_text(text: &str) -> String {
-    let mut init = String::new();
-    let (_, acc) = decode_text_tail_call(text, &mut init);
-    acc.to_string()
+fn as_element<'n, 'a>(node: &'n Node<'a>) -> Option<&'n Element<'a>> {
+    match node {
+        Node::Element(element) => Some(element),
+        Node::Text(_) => None,
+    }
 }
 
-fn decode_text_tail_call<'a>(rest: &'a str, acc: &'a mut String) -> (&'a str, &'a String) {
-    if rest.is_empty() {
-        return (rest, acc);
+// A thematic break is three or more of the same `-`, `*` or `_`, optionally
+// separated by spaces or tabs.
+fn is_thematic_break(rule: &str) -> bool {
+    let mut marks = rule.chars().filter(|c| *c != ' ' && *c != '\t');
+    let Some(first) = marks.next() else {
+        return false;
+    };
+    let mut count = 1;
+    for c in marks {
+        if c != first {
+            return false;
+        }
+        count += 1;
     }
+    matches!(first, '-' | '*' | '_') && count >= 3
+}
 
-    // entity is composed with at latest 3 characters: '&' + name + ';'
-    if rest.len() < 3 {
-        acc.push_str(rest);
-        return ("", acc);
-    }
+// Numeric references which would be Markdown or HTML syntax when decoded are
+// kept as references: `&`, `<` and `>` as named ones and the others, like `*`,
+// as they are, so that the text reads the same when it is converted again.
+pub(crate) fn decode_text(text: &str) -> String {
+    decode_entities(text, |name| {
+        let decoded = decode_entity(name);
+        match decoded.as_str() {
+            "&" => String::from("&amp;"),
+            "<" => String::from("&lt;"),
+            ">" => String::from("&gt;"),
+            "*" | "_" | "`" | "[" | "]" | "#" | "\\" => format!("&{};", name),
+            _ => decoded,
+        }
+    })
+}
 
-    let mut chars = rest.chars();
+// Unlike Markdown text, code blocks don't decode entities by themselves, so
+// the ones for HTML syntax characters are decoded in addition.
+pub(crate) fn decode_code_text(text: &str) -> String {
+    decode_entities(text, |name| match name {
+        "amp" => String::from("&"),
+        "lt" => String::from("<"),
+        "gt" => String::from(">"),
+        "quot" => String::from("\""),
+        "apos" => String::from("'"),
+        _ => decode_entity(name),
+    })
+}
 
-    match chars.next() {
-        Some('&') => match chars.position(|c| c == ';') {
-            Some(pos) => {
-                let entity_name = rest.get(1..(pos + 1)).unwrap();
-                let decoded = decode_entity(entity_name);
-                acc.push_str(&decoded);
-                decode_text_tail_call(rest.get((pos + 2)..).unwrap(), acc)
-            }
-            None => {
-                acc.push_str(rest);
-                ("", acc)
-            }
-        },
-        Some(_) => match chars.position(|c| c == '&') {
-            Some(pos) => {
-                let plain = rest.get(0..(pos + 1)).unwrap();
-                acc.push_str(plain);
-                decode_text_tail_call(rest.get((pos + 1)..).unwrap(), acc)
+// Plain text has no syntax to keep, so named references are decoded as well as
+// numeric ones, like `&nbsp;` and `&copy;`.
+pub(crate) fn decode_plain_text(text: &str) -> String {
+    decode_entities(text, |name| match named_entity(name) {
+        Some(c) => c.to_string(),
+        None => decode_entity(name),
+    })
+}
+
+// Scans the text once, replacing each `&name;` with what `decode` returns for
+// the name. An `&` followed by another `&` or by no `;` is kept as is.
+fn decode_entities(text: &str, decode: fn(&str) -> String) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        match rest[1..].find(['&', ';']).map(|pos| pos + 1) {
+            Some(end) if rest[end..].starts_with(';') => {
+                result.push_str(&decode(&rest[1..end]));
+                rest = &rest[(end + 1)..];
             }
-            None => {
-                acc.push_str(rest);
-                ("", acc)
+            Some(end) => {
+                result.push_str(&rest[..end]);
+                rest = &rest[end..];
             }
-        },
-        None => unreachable!(),
+            None => break,
+        }
     }
+    result.push_str(rest);
+
+    result
 }
 
 fn decode_entity(name: &str) -> String {
@@ -601,10 +1874,103 @@ fn decode_entity(name: &str) -> String {
     }
 }
 
+// The names of U+00A0 to U+00FF in order.
+const LATIN1_ENTITIES: [&str; 96] = [
+    "nbsp", "iexcl", "cent", "pound", "curren", "yen", "brvbar", "sect", "uml", "copy", "ordf",
+    "laquo", "not", "shy", "reg", "macr", "deg", "plusmn", "sup2", "sup3", "acute", "micro",
+    "para", "middot", "cedil", "sup1", "ordm", "raquo", "frac14", "frac12", "frac34", "iquest",
+    "Agrave", "Aacute", "Acirc", "Atilde", "Auml", "Aring", "AElig", "Ccedil", "Egrave", "Eacute",
+    "Ecirc", "Euml", "Igrave", "Iacute", "Icirc", "Iuml", "ETH", "Ntilde", "Ograve", "Oacute",
+    "Ocirc", "Otilde", "Ouml", "times", "Oslash", "Ugrave", "Uacute", "Ucirc", "Uuml", "Yacute",
+    "THORN", "szlig", "agrave", "aacute", "acirc", "atilde", "auml", "aring", "aelig", "ccedil",
+    "egrave", "eacute", "ecirc", "euml", "igrave", "iacute", "icirc", "iuml", "eth", "ntilde",
+    "ograve", "oacute", "ocirc", "otilde", "ouml", "divide", "oslash", "ugrave", "uacute", "ucirc",
+    "uuml", "yacute", "thorn", "yuml",
+];
+
+// Other named references in common use, mostly punctuation.
+const OTHER_ENTITIES: [(&str, char); 47] = [
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("OElig", '\u{152}'),
+    ("oelig", '\u{153}'),
+    ("Scaron", '\u{160}'),
+    ("scaron", '\u{161}'),
+    ("Yuml", '\u{178}'),
+    ("fnof", '\u{192}'),
+    ("circ", '\u{2C6}'),
+    ("tilde", '\u{2DC}'),
+    ("ensp", '\u{2002}'),
+    ("emsp", '\u{2003}'),
+    ("thinsp", '\u{2009}'),
+    ("zwnj", '\u{200C}'),
+    ("zwj", '\u{200D}'),
+    ("lrm", '\u{200E}'),
+    ("rlm", '\u{200F}'),
+    ("ndash", '\u{2013}'),
+    ("mdash", '\u{2014}'),
+    ("lsquo", '\u{2018}'),
+    ("rsquo", '\u{2019}'),
+    ("sbquo", '\u{201A}'),
+    ("ldquo", '\u{201C}'),
+    ("rdquo", '\u{201D}'),
+    ("bdquo", '\u{201E}'),
+    ("dagger", '\u{2020}'),
+    ("Dagger", '\u{2021}'),
+    ("bull", '\u{2022}'),
+    ("hellip", '\u{2026}'),
+    ("permil", '\u{2030}'),
+    ("prime", '\u{2032}'),
+    ("Prime", '\u{2033}'),
+    ("lsaquo", '\u{2039}'),
+    ("rsaquo", '\u{203A}'),
+    ("euro", '\u{20AC}'),
+    ("trade", '\u{2122}'),
+    ("larr", '\u{2190}'),
+    ("uarr", '\u{2191}'),
+    ("rarr", '\u{2192}'),
+    ("darr", '\u{2193}'),
+    ("harr", '\u{2194}'),
+    ("minus", '\u{2212}'),
+    ("ne", '\u{2260}'),
+    ("le", '\u{2264}'),
+];
+
+fn named_entity(name: &str) -> Option<char> {
+    if let Some(i) = LATIN1_ENTITIES.iter().position(|n| *n == name) {
+        return char::from_u32(0xA0 + i as u32);
+    }
+    OTHER_ENTITIES
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, c)| *c)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_decode_plain_text() {
+        assert_eq!(
+            decode_plain_text("a&nbsp;b &copy; &eacute;&hellip; &#233; &amp;lt; &unknown;"),
+            "a\u{A0}b \u{A9} \u{E9}\u{2026} \u{E9} &lt; &unknown;"
+        );
+    }
+
+    #[test]
+    fn test_decode_code_text() {
+        assert_eq!(decode_code_text("a &lt; b &amp;&amp; c"), "a < b && c");
+        assert_eq!(
+            decode_code_text("&amp;lt; &#38; &nbsp; & ;"),
+            "&lt; & &nbsp; & ;"
+        );
+        assert_eq!(decode_code_text("&amp"), "&amp");
+    }
+
     #[test]
     fn test_decode_text() {
         assert_eq!(decode_text("hello world"), "hello world".to_string());
@@ -615,8 +1981,73 @@ mod tests {
         assert_eq!(decode_text("&#1234;"), "Ӓ".to_string());
         assert_eq!(decode_text("&#xd06;"), "ആ".to_string());
         assert_eq!(decode_text("&#Xd06;"), "ആ".to_string());
+        assert_eq!(
+            decode_text("&#60;b&#x3e; &#38;"),
+            "&lt;b&gt; &amp;".to_string()
+        );
+
+        assert_eq!(
+            decode_text("&#42;x&#42; &#x5F;&#96;&#91;&#93;&#35;&#92;"),
+            "&#42;x&#42; &#x5F;&#96;&#91;&#93;&#35;&#92;".to_string()
+        );
 
         assert_eq!(decode_text("foo&#1234;"), "fooӒ".to_string());
         assert_eq!(decode_text("&#1234;foo"), "Ӓfoo".to_string());
+
+        assert_eq!(decode_text("&#65 &#66;"), "&#65 B".to_string());
+        assert_eq!(decode_text("foo &"), "foo &".to_string());
+        assert_eq!(decode_text("&#65;&"), "A&".to_string());
+    }
+
+    #[test]
+    fn test_decode_text_short_and_multibyte_tails() {
+        assert_eq!(decode_text("&"), "&".to_string());
+        assert_eq!(decode_text("a&"), "a&".to_string());
+        assert_eq!(decode_text("&a"), "&a".to_string());
+        assert_eq!(decode_text("日本&#65;"), "日本A".to_string());
+        assert_eq!(decode_text("&#65;日"), "A日".to_string());
+        assert_eq!(decode_text("日&"), "日&".to_string());
+        assert_eq!(decode_text("&日"), "&日".to_string());
+        assert_eq!(decode_text("&日;"), "&日;".to_string());
+        assert_eq!(decode_text("&#26085;"), "日".to_string());
+    }
+
+    #[test]
+    fn test_decode_text_long() {
+        let text = "a&#65;&b ".repeat(100_000);
+        assert_eq!(decode_text(&text), "aA&b ".repeat(100_000));
+    }
+
+    #[test]
+    fn test_list_numbers() {
+        assert_eq!(Renderer::alphabetic_number(1), "a");
+        assert_eq!(Renderer::alphabetic_number(26), "z");
+        assert_eq!(Renderer::alphabetic_number(28), "ab");
+        assert_eq!(Renderer::roman_number(4), "iv");
+        assert_eq!(Renderer::roman_number(1994), "mcmxciv");
+    }
+
+    #[test]
+    fn test_collapse_blank_lines() {
+        assert_eq!(
+            Renderer::collapse_blank_lines("a\n\n\n\nb\n\nc\n"),
+            "a\n\nb\n\nc\n"
+        );
+        assert_eq!(
+            Renderer::collapse_blank_lines("a\n\n\n```\n\n\n```\n\n\nb"),
+            "a\n\n```\n\n\n```\n\nb"
+        );
+    }
+
+    #[test]
+    fn test_trim_trailing_whitespaces() {
+        assert_eq!(
+            Renderer::trim_trailing_whitespaces("foo \n> \nbar\t\n"),
+            "foo\n>\nbar\n"
+        );
+        assert_eq!(
+            Renderer::trim_trailing_whitespaces("foo  \nbar   \n  \n"),
+            "foo  \nbar\n\n"
+        );
     }
 }
@@ -1,37 +1,192 @@
-use crate::ast::{AttributeMap, Element, Node};
+use std::borrow::Cow;
 
-pub fn restruct(node: &Node) -> Node {
+use crate::ast::{is_block_element, AttributeMap, Element, Node};
+use crate::options::{Flavor, Options};
+use crate::select::Selector;
+
+pub fn restruct<'a>(node: &Node<'a>, options: &Options) -> Node<'a> {
+    let ctx = Context::new(options);
+    restruct_node(node, &ctx)
+}
+
+// The options with ignore_selectors parsed once for the whole tree, instead of
+// for each node.
+struct Context<'o> {
+    options: &'o Options,
+    ignore_selectors: Vec<Selector>,
+}
+
+impl<'o> Context<'o> {
+    fn new(options: &'o Options) -> Self {
+        let ignore_selectors = options
+            .ignore_selectors
+            .iter()
+            .filter_map(|selector| Selector::parse(selector).ok())
+            .collect();
+        Context {
+            options,
+            ignore_selectors,
+        }
+    }
+}
+
+fn restruct_node<'a>(node: &Node<'a>, ctx: &Context) -> Node<'a> {
     match node {
-        Node::Element(element) => restruct_element(element),
+        Node::Element(element) => restruct_element(element, ctx),
         Node::Text(content) => restruct_text(content),
     }
 }
 
-fn restruct_text(content: &str) -> Node {
-    Node::Text(content.to_string())
+fn restruct_text<'a>(content: &Cow<'a, str>) -> Node<'a> {
+    Node::Text(content.clone())
 }
 
-fn restruct_element(element: &Element) -> Node {
+fn restruct_element<'a>(element: &Element<'a>, ctx: &Context) -> Node<'a> {
     let new_element = match element.tag_name.as_str() {
-        "table" => restruct_table_element(element),
-        _ => restruct_arbitrary_element(element),
+        "table" => restruct_table_element(element, ctx),
+        _ => restruct_arbitrary_element(element, ctx),
     };
     Node::Element(new_element)
 }
 
-fn restruct_arbitrary_element(element: &Element) -> Element {
-    let children = group_successive_lists(&element.children);
+fn restruct_arbitrary_element<'a>(element: &Element<'a>, ctx: &Context) -> Element<'a> {
+    let significant = remove_insignificant_whitespace(&element.tag_name, &element.children);
+    let mut children = match convert_mso_list_paragraphs(&significant) {
+        Some(converted) => group_successive_lists(&converted, ctx),
+        None => group_successive_lists(&significant, ctx),
+    };
+    if is_block_element(&element.tag_name) || element.tag_name == "th" || element.tag_name == "td" {
+        remove_trailing_br_nodes(&mut children);
+        if element.tag_name != "pre" {
+            trim_edge_text_nodes(&mut children);
+        }
+    }
+    if ctx.options.coalesce_br {
+        coalesce_br_nodes(&mut children);
+    }
+    if element.tag_name != "pre" {
+        trim_text_after_br_nodes(&mut children);
+    }
     Element::new_with_children(&element.tag_name, &element.attributes, children)
 }
 
-fn group_successive_lists(nodes: &Vec<Node>) -> Vec<Node> {
-    let mut children: Vec<Node> = Vec::new();
+// Whitespace-only text, like indentation of the source, is not rendered by
+// browsers in elements which contain no text, like <ul> and <tr>, and next to
+// blocks. Elsewhere it separates inline content like `<em>a</em> <b>b</b>`.
+fn remove_insignificant_whitespace<'a>(tag_name: &str, nodes: &[Node<'a>]) -> Vec<Node<'a>> {
+    let contains_no_text = matches!(
+        tag_name,
+        "colgroup"
+            | "dl"
+            | "head"
+            | "html"
+            | "menu"
+            | "ol"
+            | "optgroup"
+            | "select"
+            | "table"
+            | "tbody"
+            | "tfoot"
+            | "thead"
+            | "tr"
+            | "ul"
+    );
+    let is_block = |node: Option<&Node>| matches!(node, Some(Node::Element(e)) if is_block_element(&e.tag_name));
+    nodes
+        .iter()
+        .enumerate()
+        .filter(|(i, node)| match node {
+            Node::Text(text) if text.trim().is_empty() => {
+                !contains_no_text
+                    && !is_block(i.checked_sub(1).and_then(|prev| nodes.get(prev)))
+                    && !is_block(nodes.get(i + 1))
+            }
+            _ => true,
+        })
+        .map(|(_, node)| node.clone())
+        .collect()
+}
+
+// Two or more successive <br> are reduced to two, which makes a paragraph
+// break.
+fn coalesce_br_nodes(nodes: &mut Vec<Node>) {
+    let mut run = 0;
+    nodes.retain(|node| {
+        match node {
+            Node::Element(element) if element.tag_name == "br" => run += 1,
+            _ => run = 0,
+        }
+        run <= 2
+    });
+}
+
+// Whitespace at the beginning of a line made by <br> is not rendered.
+fn trim_text_after_br_nodes(nodes: &mut [Node]) {
+    for i in 1..nodes.len() {
+        if !matches!(&nodes[i - 1], Node::Element(e) if e.tag_name == "br") {
+            continue;
+        }
+        if let Node::Text(text) = &mut nodes[i] {
+            let trimmed = text.trim_start();
+            if trimmed.len() < text.len() {
+                *text = Cow::Owned(trimmed.to_string());
+            }
+        }
+    }
+}
+
+// <br> at the end of a block doesn't make a visible line.
+fn remove_trailing_br_nodes(nodes: &mut Vec<Node>) {
+    while let Some(Node::Element(last)) = nodes.last() {
+        if last.tag_name != "br" {
+            break;
+        }
+        nodes.pop();
+    }
+}
+
+// Whitespace at the beginning and the end of a block is not rendered.
+fn trim_edge_text_nodes(nodes: &mut Vec<Node>) {
+    fn trim<'a>(content: &Cow<'a, str>, f: fn(&str) -> &str) -> Cow<'a, str> {
+        match content {
+            Cow::Borrowed(s) => Cow::Borrowed(f(s)),
+            Cow::Owned(s) => Cow::Owned(f(s).to_string()),
+        }
+    }
+
+    if let Some(Node::Text(content)) = nodes.first_mut() {
+        *content = trim(content, |s| {
+            s.trim_start_matches(|c: char| c.is_ascii_whitespace())
+        });
+    }
+    if let Some(Node::Text(content)) = nodes.last_mut() {
+        *content = trim(content, |s| {
+            s.trim_end_matches(|c: char| c.is_ascii_whitespace())
+        });
+    }
+    nodes.retain(|node| !matches!(node, Node::Text(content) if content.is_empty()));
+}
+
+fn is_ignored(node: &Node, ctx: &Context) -> bool {
+    let Node::Element(element) = node else {
+        return false;
+    };
+    ctx.ignore_selectors
+        .iter()
+        .any(|selector| selector.matches(element))
+}
+
+fn group_successive_lists<'a>(nodes: &Vec<Node<'a>>, ctx: &Context) -> Vec<Node<'a>> {
+    let mut children: Vec<Node<'a>> = Vec::new();
     let mut in_successive_lists = false;
     let mut successive_lists = Vec::new();
     for child in nodes {
-        if child.is_list_element() {
+        if is_ignored(child, ctx) {
+            continue;
+        }
+        if child.is_list_element() && !ctx.options.separate_successive_lists {
             in_successive_lists = true;
-            successive_lists.push(restruct(child));
+            successive_lists.push(restruct_node(child, ctx));
         } else {
             if in_successive_lists {
                 let wrapper = Node::Element(Element::new_with_children(
@@ -43,7 +198,7 @@ fn group_successive_lists(nodes: &Vec<Node>) -> Vec<Node> {
                 successive_lists = Vec::new();
                 in_successive_lists = false;
             }
-            children.push(restruct(child));
+            children.push(restruct_node(child, ctx));
         }
     }
     if in_successive_lists {
@@ -57,6 +212,147 @@ fn group_successive_lists(nodes: &Vec<Node>) -> Vec<Node> {
     children
 }
 
+// Word exports list items as paragraphs like
+// `<p style="mso-list:l0 level1 lfo1"><span style="mso-list:ignore">1.</span>text</p>`.
+// Successive ones are converted into <ul> or <ol>, nested by their level, and
+// the spans of the markers are removed. It returns None when there are none.
+fn convert_mso_list_paragraphs<'a>(nodes: &[Node<'a>]) -> Option<Vec<Node<'a>>> {
+    if !nodes.iter().any(|node| mso_list_item(node).is_some()) {
+        return None;
+    }
+
+    let mut children = Vec::new();
+    let mut i = 0;
+    while i < nodes.len() {
+        if mso_list_item(&nodes[i]).is_none() {
+            children.push(nodes[i].clone());
+            i += 1;
+            continue;
+        }
+
+        // Lists on the stack are open at their levels, and a closed list is
+        // appended to the last item of its parent.
+        let mut stack: Vec<(usize, Element<'a>)> = Vec::new();
+        while i < nodes.len() {
+            let node = &nodes[i];
+            let Some((paragraph, level)) = mso_list_item(node) else {
+                let is_blank = matches!(node, Node::Text(content) if content.trim().is_empty());
+                let continues = nodes[i + 1..]
+                    .iter()
+                    .find(|next| !matches!(next, Node::Text(content) if content.trim().is_empty()))
+                    .is_some_and(|next| mso_list_item(next).is_some());
+                if is_blank && continues {
+                    i += 1;
+                    continue;
+                }
+                break;
+            };
+            while stack.len() > 1 && stack.last().is_some_and(|(l, _)| *l > level) {
+                close_mso_list(&mut stack);
+            }
+            if stack.last().is_none_or(|(l, _)| *l < level) {
+                let tag_name = if is_ordered_mso_marker(paragraph) {
+                    "ol"
+                } else {
+                    "ul"
+                };
+                stack.push((level, Element::new(tag_name, &AttributeMap::new())));
+            }
+            let item = Element::new_with_children(
+                "li",
+                &AttributeMap::new(),
+                remove_mso_markers(&paragraph.children),
+            );
+            if let Some((_, list)) = stack.last_mut() {
+                list.children.push(Node::Element(item));
+            }
+            i += 1;
+        }
+        while stack.len() > 1 {
+            close_mso_list(&mut stack);
+        }
+        if let Some((_, list)) = stack.pop() {
+            children.push(Node::Element(list));
+        }
+    }
+    Some(children)
+}
+
+fn close_mso_list(stack: &mut Vec<(usize, Element)>) {
+    let Some((_, list)) = stack.pop() else {
+        return;
+    };
+    if let Some((_, parent)) = stack.last_mut() {
+        if let Some(Node::Element(item)) = parent.children.last_mut() {
+            item.children.push(Node::Element(list));
+        }
+    }
+}
+
+fn mso_list_item<'n, 'a>(node: &'n Node<'a>) -> Option<(&'n Element<'a>, usize)> {
+    let Node::Element(element) = node else {
+        return None;
+    };
+    if element.tag_name != "p" {
+        return None;
+    }
+    let (_, value) = element
+        .style_declarations()
+        .into_iter()
+        .find(|(property, _)| property == "mso-list")?;
+    value
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix("level"))
+        .and_then(|level| level.parse::<usize>().ok())
+        .map(|level| (element, level))
+}
+
+fn is_mso_marker(node: &Node) -> bool {
+    match node {
+        Node::Element(element) => element
+            .style_declarations()
+            .iter()
+            .any(|(property, value)| property == "mso-list" && value == "ignore"),
+        Node::Text(_) => false,
+    }
+}
+
+// A marker like `1.`, `a)` or `iv.` makes an ordered list, and a bullet like
+// `·` or `o` an unordered one.
+fn is_ordered_mso_marker(paragraph: &Element) -> bool {
+    let mut marker = String::new();
+    for child in &paragraph.children {
+        child.walk(&mut |node| {
+            if is_mso_marker(node) {
+                marker.push_str(&node.text_content());
+            }
+        });
+    }
+    let marker: String = marker
+        .chars()
+        .take_while(|c| !c.is_whitespace() && *c != '&')
+        .collect();
+    match marker.strip_suffix(['.', ')']) {
+        Some(number) => !number.is_empty() && number.chars().all(|c| c.is_alphanumeric()),
+        None => false,
+    }
+}
+
+fn remove_mso_markers<'a>(nodes: &[Node<'a>]) -> Vec<Node<'a>> {
+    nodes
+        .iter()
+        .filter(|node| !is_mso_marker(node))
+        .map(|node| match node {
+            Node::Element(element) => Node::Element(Element::new_with_children(
+                &element.tag_name,
+                &element.attributes,
+                remove_mso_markers(&element.children),
+            )),
+            Node::Text(content) => Node::Text(content.clone()),
+        })
+        .collect()
+}
+
 // Ensure TABLE element structure as follows:
 //
 //   TABLE
@@ -65,31 +361,67 @@ fn group_successive_lists(nodes: &Vec<Node>) -> Vec<Node> {
 //     TBODY
 //       TR*
 //
-fn restruct_table_element(element: &Element) -> Element {
+// A table is kept as it is and wrapped with `html2md:html-table` so that it
+// can be rendered in HTML form when it is irregular and `table_fallback_html`
+// is enabled, or when the flavor lacks tables.
+//
+fn restruct_table_element<'a>(element: &Element<'a>, ctx: &Context) -> Element<'a> {
     let mut new_element = Element::new("table", &element.attributes);
 
     let mut tr_nodes = Vec::new();
     for child in &element.children {
-        let mut child_tr_nodes = collect_tr_nodes(child);
+        let mut child_tr_nodes = collect_tr_nodes(child, ctx);
         tr_nodes.append(&mut child_tr_nodes);
     }
 
+    if ctx.options.flavor == Flavor::CommonMark
+        || (ctx.options.table_fallback_html && is_irregular_table(&tr_nodes))
+    {
+        return Element::new_with_children(
+            "html2md:html-table",
+            &AttributeMap::new(),
+            vec![Node::Element(element.clone())],
+        );
+    }
+
     if tr_nodes.is_empty() {
         return new_element;
     }
 
-    let head_tr_node = tr_nodes[0].clone();
+    let alignments = column_alignments(element, ctx);
+    if !alignments.is_empty() {
+        apply_column_alignments(&mut tr_nodes, &alignments);
+    }
+
+    // All rows of a leading <thead> are header rows, otherwise the first row
+    // is.
+    let head_tr_count = element
+        .children
+        .iter()
+        .find_map(|child| match child {
+            Node::Element(e) if !is_ignored(child, ctx) => Some(e),
+            _ => None,
+        })
+        .filter(|first| first.tag_name == "thead")
+        .map_or(0, |thead| {
+            thead
+                .children
+                .iter()
+                .filter(|child| matches!(child, Node::Element(e) if e.tag_name == "tr"))
+                .filter(|child| !is_ignored(child, ctx))
+                .count()
+        })
+        .clamp(1, tr_nodes.len());
+
+    let mut body_tr_nodes = tr_nodes;
+    let head_tr_nodes: Vec<Node<'a>> = body_tr_nodes.drain(..head_tr_count).collect();
     let thead_node = Node::Element(Element::new_with_children(
         "thead",
         &AttributeMap::new(),
-        vec![head_tr_node],
+        head_tr_nodes,
     ));
     new_element.children.push(thead_node);
 
-    let mut body_tr_nodes: Vec<Node> = Vec::new();
-    for tr_node in tr_nodes.into_iter().skip(1).collect::<Vec<Node>>() {
-        body_tr_nodes.push(tr_node.clone());
-    }
     let tbody_node = Node::Element(Element::new_with_children(
         "tbody",
         &AttributeMap::new(),
@@ -100,14 +432,163 @@ fn restruct_table_element(element: &Element) -> Element {
     new_element
 }
 
-fn collect_tr_nodes(node: &Node) -> Vec<Node> {
+// A table is irregular when its rows occupy different numbers of columns after
+// expanding colspan and rowspan.
+fn is_irregular_table(tr_nodes: &[Node]) -> bool {
+    row_widths(tr_nodes).windows(2).any(|w| w[0] != w[1])
+}
+
+fn row_widths(tr_nodes: &[Node]) -> Vec<usize> {
+    let mut widths = Vec::new();
+    let mut pending_rowspans: Vec<usize> = Vec::new();
+
+    for tr_node in tr_nodes {
+        let Node::Element(tr) = tr_node else {
+            continue;
+        };
+
+        let mut occupied: Vec<bool> = pending_rowspans.iter().map(|n| *n > 0).collect();
+        let mut column = 0;
+        for cell in &tr.children {
+            let Node::Element(cell) = cell else {
+                continue;
+            };
+            if cell.tag_name != "th" && cell.tag_name != "td" {
+                continue;
+            }
+
+            while column < occupied.len() && occupied[column] {
+                column += 1;
+            }
+
+            let colspan = span_attribute(cell, "colspan");
+            let rowspan = span_attribute(cell, "rowspan");
+            for c in column..(column + colspan) {
+                if c >= occupied.len() {
+                    occupied.resize(c + 1, false);
+                    pending_rowspans.resize(c + 1, 0);
+                }
+                occupied[c] = true;
+                pending_rowspans[c] = rowspan;
+            }
+            column += colspan;
+        }
+
+        widths.push(occupied.iter().filter(|o| **o).count());
+        for n in pending_rowspans.iter_mut() {
+            *n = n.saturating_sub(1);
+        }
+    }
+
+    widths
+}
+
+// The alignments of the columns given by <col>, which may be grouped in
+// <colgroup>. It is empty without them.
+fn column_alignments(element: &Element, ctx: &Context) -> Vec<Option<String>> {
+    let mut alignments = Vec::new();
+    for child in &element.children {
+        let Node::Element(e) = child else {
+            continue;
+        };
+        if is_ignored(child, ctx) {
+            continue;
+        }
+        match e.tag_name.as_str() {
+            "col" => {
+                let align = e.text_alignment();
+                alignments.extend(std::iter::repeat_n(align, span_attribute(e, "span")));
+            }
+            "colgroup" => {
+                let group_align = e.text_alignment();
+                let cols: Vec<&Element> = e
+                    .children
+                    .iter()
+                    .filter_map(|node| match node {
+                        Node::Element(col) if col.tag_name == "col" => Some(col),
+                        _ => None,
+                    })
+                    .collect();
+                if cols.is_empty() {
+                    let span = span_attribute(e, "span");
+                    alignments.extend(std::iter::repeat_n(group_align.clone(), span));
+                }
+                for col in cols {
+                    let align = col.text_alignment().or(group_align.clone());
+                    alignments.extend(std::iter::repeat_n(align, span_attribute(col, "span")));
+                }
+            }
+            _ => {}
+        }
+    }
+    alignments
+}
+
+// Rows narrower than the columns are padded with empty cells, and the cells of
+// the first row take the alignments of their columns unless they have ones.
+fn apply_column_alignments(tr_nodes: &mut [Node], alignments: &[Option<String>]) {
+    let widths = row_widths(tr_nodes);
+    for (i, (tr_node, width)) in tr_nodes.iter_mut().zip(widths).enumerate() {
+        let Node::Element(tr) = tr_node else {
+            continue;
+        };
+
+        if i == 0 {
+            let mut column = 0;
+            for cell in tr.children.iter_mut() {
+                let Node::Element(cell) = cell else {
+                    continue;
+                };
+                if cell.tag_name != "th" && cell.tag_name != "td" {
+                    continue;
+                }
+                if let Some(Some(align)) = alignments.get(column) {
+                    if cell.text_alignment().is_none() {
+                        cell.attributes.insert("align".to_string(), align.clone());
+                    }
+                }
+                column += span_attribute(cell, "colspan");
+            }
+        }
+
+        let tag_name = match tr.children.iter().rev().find_map(|node| match node {
+            Node::Element(cell) if cell.tag_name == "th" || cell.tag_name == "td" => Some(cell),
+            _ => None,
+        }) {
+            Some(cell) => cell.tag_name.clone(),
+            None => String::from("td"),
+        };
+        for _ in width..alignments.len() {
+            tr.children
+                .push(Node::Element(Element::new(&tag_name, &AttributeMap::new())));
+        }
+    }
+}
+
+// Spans are clamped like browsers do, so that a huge one doesn't exhaust
+// memory.
+pub(crate) fn span_attribute(element: &Element, name: &str) -> usize {
+    let limit = match name {
+        "rowspan" => 65534,
+        _ => 1000,
+    };
+    match element.attributes.get(name) {
+        Some(value) => value.trim().parse::<usize>().unwrap_or(1).clamp(1, limit),
+        None => 1,
+    }
+}
+
+fn collect_tr_nodes<'a>(node: &Node<'a>, ctx: &Context) -> Vec<Node<'a>> {
+    if is_ignored(node, ctx) {
+        return Vec::new();
+    }
     match node {
         Node::Element(element) => match element.tag_name.as_str() {
-            "tr" => vec![node.clone()],
+            "tr" => vec![restruct_node(node, ctx)],
             _ => {
                 let mut nodes = Vec::new();
                 for child in &element.children {
-                    let mut children = collect_tr_nodes(child);
+                    let mut children = collect_tr_nodes(child, ctx);
                     nodes.append(&mut children);
                 }
                 nodes
@@ -121,7 +602,7 @@ fn collect_tr_nodes(node: &Node) -> Vec<Node> {
 mod tests {
     use super::*;
 
-    fn new_element(tag_name: &str, children: Vec<Node>) -> Node {
+    fn new_element(tag_name: &str, children: Vec<Node<'static>>) -> Node<'static> {
         Node::Element(Element::new_with_children(
             tag_name,
             &AttributeMap::new(),
@@ -132,13 +613,13 @@ mod tests {
     fn new_element_with_attributes(
         tag_name: &str,
         attributes: &AttributeMap,
-        children: Vec<Node>,
-    ) -> Node {
+        children: Vec<Node<'static>>,
+    ) -> Node<'static> {
         Node::Element(Element::new_with_children(tag_name, attributes, children))
     }
 
-    fn new_text(content: &str) -> Node {
-        Node::Text(content.to_string())
+    fn new_text(content: &str) -> Node<'static> {
+        Node::Text(content.to_string().into())
     }
 
     #[test]
@@ -171,7 +652,7 @@ mod tests {
                         ),
                     ],
                 ),
-                Node::Text("Hello".to_string()),
+                Node::Text("Hello".into()),
             ],
         );
 
@@ -207,7 +688,7 @@ mod tests {
             ],
         );
 
-        assert_eq!(restruct(&original_node), expected_node);
+        assert_eq!(restruct(&original_node, &Options::default()), expected_node);
     }
 
     #[test]
@@ -270,7 +751,166 @@ mod tests {
             ],
         );
 
-        assert_eq!(restruct(&original_node), expected_node);
+        assert_eq!(restruct(&original_node, &Options::default()), expected_node);
+    }
+
+    #[test]
+    fn test_restruct_irregular_table() {
+        let tr_nodes = vec![
+            new_element(
+                "tr",
+                vec![
+                    new_element("th", vec![new_text("1,1")]),
+                    new_element("th", vec![new_text("1,2")]),
+                ],
+            ),
+            new_element(
+                "tr",
+                vec![
+                    new_element_with_attributes(
+                        "td",
+                        &AttributeMap::from([("rowspan".to_string(), "2".to_string())]),
+                        vec![new_text("2,1")],
+                    ),
+                    new_element("td", vec![new_text("2,2")]),
+                ],
+            ),
+            new_element("tr", vec![new_element("td", vec![new_text("3,2")])]),
+        ];
+        assert!(!is_irregular_table(&tr_nodes));
+
+        let original_node = new_element(
+            "body",
+            vec![new_element(
+                "table",
+                vec![
+                    new_element("tr", vec![new_element("th", vec![new_text("1,1")])]),
+                    new_element(
+                        "tr",
+                        vec![
+                            new_element("td", vec![new_text("2,1")]),
+                            new_element("td", vec![new_text("2,2")]),
+                        ],
+                    ),
+                ],
+            )],
+        );
+
+        let options = Options {
+            table_fallback_html: true,
+            ..Default::default()
+        };
+        let Node::Element(original_body) = &original_node else {
+            unreachable!()
+        };
+        let expected_node = new_element(
+            "body",
+            vec![new_element(
+                "html2md:html-table",
+                vec![original_body.children[0].clone()],
+            )],
+        );
+
+        assert_eq!(restruct(&original_node, &options), expected_node);
+    }
+
+    #[test]
+    fn test_restruct_trailing_br() {
+        let original_node = new_element(
+            "body",
+            vec![
+                new_element(
+                    "p",
+                    vec![
+                        new_text("hello"),
+                        new_element("br", vec![]),
+                        new_text("world"),
+                        new_element("br", vec![]),
+                        new_element("br", vec![]),
+                    ],
+                ),
+                new_element("br", vec![]),
+            ],
+        );
+
+        let expected_node = new_element(
+            "body",
+            vec![
+                new_element(
+                    "p",
+                    vec![
+                        new_text("hello"),
+                        new_element("br", vec![]),
+                        new_text("world"),
+                    ],
+                ),
+                new_element("br", vec![]),
+            ],
+        );
+
+        assert_eq!(restruct(&original_node, &Options::default()), expected_node);
+    }
+
+    #[test]
+    fn test_restruct_mso_list_paragraphs() {
+        let paragraph = |level: usize, marker: &'static str, text: &'static str| {
+            new_element_with_attributes(
+                "p",
+                &AttributeMap::from([(
+                    "style".to_string(),
+                    format!("text-indent:-.25in;mso-list:l0 level{} lfo1", level),
+                )]),
+                vec![
+                    new_element_with_attributes(
+                        "span",
+                        &AttributeMap::from([("style".to_string(), "mso-list:ignore".to_string())]),
+                        vec![new_text(marker)],
+                    ),
+                    new_text(text),
+                ],
+            )
+        };
+        let original_node = new_element(
+            "body",
+            vec![
+                new_element("p", vec![new_text("hello")]),
+                paragraph(1, "1.", "one"),
+                new_text("\n"),
+                paragraph(2, "·", "nested"),
+                paragraph(1, "2.", "two"),
+            ],
+        );
+
+        let expected_node = new_element(
+            "body",
+            vec![
+                new_element("p", vec![new_text("hello")]),
+                new_element(
+                    "html2md:successive-lists-wrapper",
+                    vec![new_element(
+                        "ol",
+                        vec![
+                            new_element(
+                                "li",
+                                vec![
+                                    new_text("one"),
+                                    new_element(
+                                        "html2md:successive-lists-wrapper",
+                                        vec![new_element(
+                                            "ul",
+                                            vec![new_element("li", vec![new_text("nested")])],
+                                        )],
+                                    ),
+                                ],
+                            ),
+                            new_element("li", vec![new_text("two")]),
+                        ],
+                    )],
+                ),
+            ],
+        );
+
+        assert_eq!(restruct(&original_node, &Options::default()), expected_node);
     }
 
     #[test]
@@ -302,6 +942,6 @@ mod tests {
             ],
         );
 
-        assert_eq!(restruct(&original_node), expected_node);
+        assert_eq!(restruct(&original_node, &Options::default()), expected_node);
     }
 }
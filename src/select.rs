@@ -0,0 +1,130 @@
+use std::fmt;
+
+use crate::ast::{Element, Node};
+
+pub type Result<T> = std::result::Result<T, SelectError>;
+
+#[derive(Debug, PartialEq)]
+pub enum SelectError {
+    InvalidSelector(String),
+    NotFound(String),
+}
+
+impl fmt::Display for SelectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SelectError::InvalidSelector(selector) => write!(f, "invalid selector: {}", selector),
+            SelectError::NotFound(selector) => write!(f, "no element matches {}", selector),
+        }
+    }
+}
+
+impl std::error::Error for SelectError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            SelectError::InvalidSelector(_) => None,
+            SelectError::NotFound(_) => None,
+        }
+    }
+}
+
+// A simple selector. Tag names are lowercased like the tokenizer does, while
+// ids and classes are case-sensitive.
+#[derive(Debug, PartialEq)]
+pub enum Selector {
+    Id(String),    // #id
+    Class(String), // .class
+    Tag(String),   // tag
+}
+
+impl Selector {
+    pub fn parse(selector: &str) -> Result<Self> {
+        let trimmed = selector.trim();
+        let (make, name): (fn(String) -> Self, &str) = if let Some(id) = trimmed.strip_prefix('#') {
+            (Self::Id, id)
+        } else if let Some(class) = trimmed.strip_prefix('.') {
+            (Self::Class, class)
+        } else {
+            (|tag: String| Self::Tag(tag.to_lowercase()), trimmed)
+        };
+
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(SelectError::InvalidSelector(selector.to_string()));
+        }
+        Ok(make(name.to_string()))
+    }
+
+    pub fn matches(&self, element: &Element) -> bool {
+        match self {
+            Self::Id(id) => element.attributes.get("id") == Some(id),
+            Self::Class(class) => element.css_classes().contains(class),
+            Self::Tag(tag) => element.tag_name == *tag,
+        }
+    }
+}
+
+// Returns the first element matching the selector in document order.
+pub fn select_first<'n, 'a>(node: &'n Node<'a>, selector: &Selector) -> Option<&'n Element<'a>> {
+    let Node::Element(element) = node else {
+        return None;
+    };
+    if selector.matches(element) {
+        return Some(element);
+    }
+    element
+        .children
+        .iter()
+        .find_map(|child| select_first(child, selector))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_html;
+
+    #[test]
+    fn test_selector_parse() {
+        assert_eq!(
+            Selector::parse("#Main"),
+            Ok(Selector::Id("Main".to_string()))
+        );
+        assert_eq!(
+            Selector::parse(".note"),
+            Ok(Selector::Class("note".to_string()))
+        );
+        assert_eq!(
+            Selector::parse(" DIV "),
+            Ok(Selector::Tag("div".to_string()))
+        );
+        for selector in ["", "#", ".", "div.note", "a > b"] {
+            assert_eq!(
+                Selector::parse(selector),
+                Err(SelectError::InvalidSelector(selector.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_select_first() {
+        let source = "<html><head></head><body><p class=\"a b\">1</p><div id=\"x\"><p class=\"b\">2</p></div></body></html>";
+        let node = match parse_html(source) {
+            Ok(node) => node,
+            Err(e) => panic!("Unexpected Err({:?})", e),
+        };
+
+        let text = |selector: &str| {
+            let selector = Selector::parse(selector).unwrap();
+            select_first(&node, &selector)
+                .map(|element| Node::Element(element.clone()).text_content())
+        };
+        assert_eq!(text("#x"), Some("2".to_string()));
+        assert_eq!(text(".b"), Some("1".to_string()));
+        assert_eq!(text("div"), Some("2".to_string()));
+        assert_eq!(text("#y"), None);
+        assert_eq!(text("#X"), None);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::ast::{is_void_element, Element, Node};
+use crate::options::Options;
+
+// Serializes a node and its descendants back to HTML, like the tree returned
+// by `parse` or `restruct`. Text is emitted as it is in the source, so
+// entities are kept escaped.
+pub fn serialize_html(node: &Node) -> String {
+    serialize_html_with_options(node, &Options::default())
+}
+
+// Attributes are emitted following `sort_attributes` and
+// `bare_boolean_attributes` of the options.
+pub fn serialize_html_with_options(node: &Node, options: &Options) -> String {
+    let mut result = String::new();
+    write_node(node, options, &mut result);
+    result
+}
+
+pub(crate) fn open_tag(element: &Element, options: &Options) -> String {
+    let mut open_tag = String::new();
+    open_tag.push('<');
+    open_tag.push_str(&element.tag_name);
+    if !element.attributes.is_empty() {
+        let mut attributes: Vec<(&String, &String)> = element.attributes.iter().collect();
+        if options.sort_attributes {
+            attributes.sort();
+        }
+
+        for (name, value) in attributes {
+            if options.bare_boolean_attributes && name == value {
+                open_tag.push_str(&format!(" {}", name));
+            } else {
+                open_tag.push_str(&format!(" {}=\"{}\"", name, escape_attribute_value(value)));
+            }
+        }
+    }
+    open_tag.push('>');
+    open_tag
+}
+
+// Values are kept as they are in the source, so character references like
+// `&amp;` are already escaped. Other `&` and `"`, like in single-quoted
+// values, are escaped to keep the value in the double quotes.
+fn escape_attribute_value(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    for (i, c) in value.char_indices() {
+        match c {
+            '&' if !starts_with_reference(&value[i..]) => result.push_str("&amp;"),
+            '"' => result.push_str("&quot;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+fn starts_with_reference(text: &str) -> bool {
+    let rest = &text[1..];
+    match rest.find(';') {
+        Some(end) => {
+            let name = rest[..end].strip_prefix('#').unwrap_or(&rest[..end]);
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric())
+        }
+        None => false,
+    }
+}
+
+fn write_node(node: &Node, options: &Options, buffer: &mut String) {
+    match node {
+        Node::Element(element) => {
+            buffer.push_str(&open_tag(element, options));
+            if !is_void_element(&element.tag_name) {
+                for child in &element.children {
+                    write_node(child, options, buffer);
+                }
+                buffer.push_str(&format!("</{}>", &element.tag_name));
+            }
+        }
+        Node::Text(content) => buffer.push_str(content),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Parser;
+    use crate::tokenize::Tokenizer;
+
+    #[test]
+    fn test_serialize_html() {
+        for source in [
+            "<div id=\"a\"><p>hello <b>world</b><br></p><ul><li>1</li><li>2 &amp; 3</li></ul></div>",
+            "<table><tr><td colspan=\"2\"><img src=\"a.png\">x</td></tr></table>",
+            "<svg width=\"16\"><g><path d=\"M0 0L1 1\"></path></g></svg>",
+        ] {
+            let tokens = Tokenizer::new(source).tokenize().unwrap();
+            let node = Parser::new(&tokens).parse().unwrap();
+            assert_eq!(serialize_html(&node), source);
+        }
+    }
+
+    #[test]
+    fn test_serialize_html_with_options() {
+        let tokens = Tokenizer::new("<input type=\"checkbox\" checked>")
+            .tokenize()
+            .unwrap();
+        let node = Parser::new(&tokens).parse().unwrap();
+        let options = Options {
+            sort_attributes: true,
+            bare_boolean_attributes: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            serialize_html_with_options(&node, &options),
+            "<input checked type=\"checkbox\">"
+        );
+    }
+
+    #[test]
+    fn test_serialize_html_escaping_attribute_values() {
+        let tokens = Tokenizer::new("<img alt='say \"hi\" &amp; R&D' src=a.png>")
+            .tokenize()
+            .unwrap();
+        let node = Parser::new(&tokens).parse().unwrap();
+        assert_eq!(
+            serialize_html(&node),
+            "<img alt=\"say &quot;hi&quot; &amp; R&amp;D\" src=\"a.png\">"
+        );
+    }
+}
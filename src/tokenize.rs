@@ -1,6 +1,5 @@
-use std::char;
+use std::borrow::Cow;
 use std::fmt;
-use std::str::Chars;
 
 use crate::ast::{is_void_element, AttributeMap, Tag, TagKind, Token};
 
@@ -9,6 +8,7 @@ pub type Result<T> = std::result::Result<T, TokenizeError>;
 #[derive(Debug, PartialEq)]
 pub enum TokenizeError {
     Malformed,
+    TooLarge(usize, usize),     // (limit, actual) in bytes
     UnexpectedChar(char, char), // (expected, actual)
     UnexpectedEOF,
 }
@@ -19,6 +19,9 @@ impl fmt::Display for TokenizeError {
             TokenizeError::Malformed => {
                 write!(f, "syntactically malformed token found and ignored")
             }
+            TokenizeError::TooLarge(limit, actual) => {
+                write!(f, "input of {} bytes exceeds limit of {}", actual, limit)
+            }
             TokenizeError::UnexpectedChar(expected, actual) => {
                 write!(f, "expected {} but got {}", expected, actual)
             }
@@ -31,6 +34,7 @@ impl std::error::Error for TokenizeError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match *self {
             TokenizeError::Malformed => None,
+            TokenizeError::TooLarge(..) => None,
             TokenizeError::UnexpectedChar(..) => None,
             TokenizeError::UnexpectedEOF => None,
         }
@@ -38,54 +42,59 @@ impl std::error::Error for TokenizeError {
 }
 
 pub struct Tokenizer<'a> {
-    chars: std::iter::Peekable<Chars<'a>>,
+    source: &'a str,
+    pos: usize, // byte index into source
+    warnings: Vec<String>,
+    malformed: Vec<usize>, // byte indices where skipped malformed tokens begin
+    svg_depth: usize,      // the number of open <svg> tags
+    in_document: bool,     // whether a tag has been read
 }
 
 impl<'a> Tokenizer<'a> {
     pub fn new(source: &'a str) -> Self {
         Self {
-            chars: source.chars().peekable(),
+            source,
+            pos: 0,
+            warnings: Vec::new(),
+            malformed: Vec::new(),
+            svg_depth: 0,
+            in_document: false,
         }
     }
 
-    pub fn tokenize(&mut self) -> Result<Vec<Token>> {
-        let mut tokens = Vec::new();
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
 
-        loop {
-            self.skip_whitespaces();
+    // The number of malformed tokens skipped so far, which tells how much of
+    // a broken source was dropped.
+    pub fn malformed_count(&self) -> usize {
+        self.malformed.len()
+    }
 
-            if self.is_eof() {
-                break;
-            }
+    pub fn malformed_positions(&self) -> &[usize] {
+        &self.malformed
+    }
 
-            match self.read_token() {
-                Ok(Token::Sgml) => continue,
-                Ok(token) => tokens.push(token),
-                Err(e) => {
-                    if e == TokenizeError::Malformed {
-                        continue;
-                    } else {
-                        return Err(e);
-                    }
-                }
-            }
-        }
+    pub fn tokenize(&mut self) -> Result<Vec<Token<'a>>> {
+        self.collect()
+    }
 
-        Ok(tokens)
+    fn rest(&self) -> &'a str {
+        &self.source[self.pos..]
     }
 
     fn skip_whitespaces(&mut self) {
-        while self.chars.next_if(|c| c.is_ascii_whitespace()).is_some() {
-            continue;
-        }
+        self.read_while(|c| c.is_ascii_whitespace());
     }
 
-    fn is_eof(&mut self) -> bool {
-        self.chars.peek().is_none()
+    fn is_eof(&self) -> bool {
+        self.pos >= self.source.len()
     }
 
-    fn read_token(&mut self) -> Result<Token> {
-        if self.consume_char('<') {
+    fn read_token(&mut self) -> Result<Token<'a>> {
+        if self.starts_with_tag() {
+            self.consume_char('<');
             if self.consume_char('!') {
                 self.read_sgml()
             } else {
@@ -96,26 +105,20 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
-    fn read_sgml(&mut self) -> Result<Token> {
-        loop {
-            match self.chars.peek() {
-                Some(c) => {
-                    if *c == '>' {
-                        self.chars.next();
-                        break;
-                    } else {
-                        self.chars.next();
-                        continue;
-                    }
-                }
-                None => return Err(TokenizeError::UnexpectedEOF),
+    fn read_sgml(&mut self) -> Result<Token<'a>> {
+        match self.rest().find('>') {
+            Some(end) => {
+                self.pos += end + 1;
+                Ok(Token::Sgml)
+            }
+            None => {
+                self.pos = self.source.len();
+                Err(TokenizeError::UnexpectedEOF)
             }
         }
-
-        Ok(Token::Sgml)
     }
 
-    fn read_tag(&mut self) -> Result<Token> {
+    fn read_tag(&mut self) -> Result<Token<'a>> {
         let beginning_with_slash = self.consume_char('/');
         let name = self.read_tag_name()?;
         let (attributes, ending_with_slash) = self.read_attributes()?;
@@ -136,16 +139,29 @@ impl<'a> Tokenizer<'a> {
                 }))
             }
         } else {
-            // <foo> and </foo> are allowed for non-void element. <foo/> or </foo/> are not.
-            if ending_with_slash {
+            // <foo> and </foo> are allowed for non-void element. <foo/> or </foo/> are not,
+            // except that <foo/> is an empty element in SVG.
+            if ending_with_slash && !beginning_with_slash && self.svg_depth > 0 {
+                Ok(Token::Tag(Tag {
+                    name,
+                    attributes,
+                    kind: TagKind::Void,
+                }))
+            } else if ending_with_slash {
                 Err(TokenizeError::Malformed)
             } else if beginning_with_slash {
+                if name == "svg" {
+                    self.svg_depth = self.svg_depth.saturating_sub(1);
+                }
                 Ok(Token::Tag(Tag {
                     name,
                     attributes,
                     kind: TagKind::Close,
                 }))
             } else {
+                if name == "svg" {
+                    self.svg_depth += 1;
+                }
                 Ok(Token::Tag(Tag {
                     name,
                     attributes,
@@ -156,20 +172,11 @@ impl<'a> Tokenizer<'a> {
     }
 
     fn read_tag_name(&mut self) -> Result<String> {
-        let mut name = String::new();
-        loop {
-            match self.chars.peek() {
-                Some(c) => {
-                    if c.is_alphanumeric() {
-                        name.push(*c);
-                        self.chars.next();
-                        continue;
-                    } else {
-                        break;
-                    }
-                }
-                None => return Err(TokenizeError::UnexpectedEOF),
-            }
+        // Custom elements have `-` and namespaced ones `:` in their names, like
+        // `<my-widget>` and `<svg:rect>`.
+        let name = self.read_while(|c| c.is_alphanumeric() || c == '-' || c == ':');
+        if self.is_eof() {
+            return Err(TokenizeError::UnexpectedEOF);
         }
 
         Ok(name.to_ascii_lowercase())
@@ -192,78 +199,115 @@ impl<'a> Tokenizer<'a> {
             }
 
             let name = self.read_attribute_name()?;
+            if name.is_empty() {
+                return Err(TokenizeError::Malformed);
+            }
+            // Whitespace is allowed around `=`, like `href = "x"`.
+            self.skip_whitespaces();
             let value = if self.consume_char('=') {
+                self.skip_whitespaces();
                 self.read_attribute_value()?
             } else {
                 name.clone()
             };
 
-            attributes.insert(name, value);
+            // The first one wins like browsers do.
+            if attributes.contains_key(&name) {
+                self.warnings
+                    .push(format!("duplicate `{}` attribute is ignored.", name));
+            } else {
+                attributes.insert(name, value);
+            }
         }
 
         Ok((attributes, ending_with_slash))
     }
 
     fn read_attribute_name(&mut self) -> Result<String> {
-        let mut result = String::new();
-
-        loop {
-            match self.chars.peek() {
-                Some(actual) => {
-                    if actual.is_ascii_alphanumeric() || *actual == '-' || *actual == '_' {
-                        result.push(*actual);
-                        self.chars.next();
-                    } else {
-                        break;
-                    }
-                }
-                None => return Err(TokenizeError::UnexpectedEOF),
-            }
+        // `.` and `:` appear in names like `v-bind:href`, `data.foo` and
+        // `xml:lang` of frameworks and XHTML.
+        let name =
+            self.read_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'));
+        if self.is_eof() {
+            return Err(TokenizeError::UnexpectedEOF);
         }
 
-        Ok(result.to_lowercase())
+        Ok(name.to_lowercase())
     }
 
+    // Unlike names, values are case-sensitive, like URLs and alt text.
+    // Values may be quoted with `"` or `'`, or unquoted like
+    // `class=MsoListParagraph` of HTML exported by Word.
     fn read_attribute_value(&mut self) -> Result<String> {
-        let mut result = String::new();
-
-        self.expect_char('"')?;
-
-        loop {
-            match self.chars.peek() {
-                Some(actual) => {
-                    if *actual == '"' {
-                        self.chars.next();
-                        break;
-                    } else {
-                        result.push(*actual);
-                        self.chars.next();
-                        continue;
-                    }
+        let quote = match self.rest().chars().next() {
+            Some(c @ ('"' | '\'')) => c,
+            Some(_) => {
+                let value = self.read_while(|c| !c.is_ascii_whitespace() && c != '>');
+                if value.is_empty() {
+                    return Err(TokenizeError::Malformed);
                 }
-                None => return Err(TokenizeError::UnexpectedEOF),
+                return Ok(value.to_string());
+            }
+            None => return Err(TokenizeError::UnexpectedEOF),
+        };
+        self.expect_char(quote)?;
+
+        match self.rest().find(quote) {
+            Some(end) => {
+                let value = &self.rest()[..end];
+                self.pos += end + 1;
+                Ok(value.to_string())
+            }
+            None => {
+                self.pos = self.source.len();
+                Err(TokenizeError::UnexpectedEOF)
             }
         }
+    }
 
-        Ok(result.to_lowercase())
+    // `<` is a part of text unless it starts a tag, like `1 < 2`, as browsers
+    // do. Letters other than ASCII are allowed in tag names like `<Ä>`.
+    fn starts_with_tag(&self) -> bool {
+        let mut chars = self.rest().chars();
+        chars.next() == Some('<')
+            && matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '/' || c == '!')
     }
 
-    fn read_text(&mut self) -> Result<Token> {
-        let mut content = String::new();
-        while let Some(c) = self.chars.next_if(|c| *c != '<') {
-            content.push(c)
+    fn read_text(&mut self) -> Result<Token<'a>> {
+        let start = self.pos;
+        loop {
+            self.read_while(|c| c != '<');
+            if self.is_eof() || self.starts_with_tag() {
+                break;
+            }
+            self.consume_char('<');
         }
 
-        Ok(Token::Text(content))
+        Ok(Token::Text(Cow::Borrowed(&self.source[start..self.pos])))
+    }
+
+    // Advances over the characters satisfying the predicate and returns them
+    // as a slice of the source.
+    fn read_while<F: Fn(char) -> bool>(&mut self, predicate: F) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
     }
 
     fn consume_char(&mut self, expected: char) -> bool {
-        self.chars.next_if(|c| *c == expected).is_some()
+        if self.rest().starts_with(expected) {
+            self.pos += expected.len_utf8();
+            true
+        } else {
+            false
+        }
     }
 
     fn expect_char(&mut self, expected: char) -> Result<()> {
-        match self.chars.next() {
+        match self.rest().chars().next() {
             Some(actual) => {
+                self.pos += actual.len_utf8();
                 if actual == expected {
                     Ok(())
                 } else {
@@ -275,6 +319,44 @@ impl<'a> Tokenizer<'a> {
     }
 }
 
+// Yields tokens lazily. Malformed tokens are skipped, and nothing is yielded
+// after an error.
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<Token<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // Whitespace before the root element is insignificant, while the
+            // one starting a text token is kept, like ` b` of `a<b> b</b>`.
+            if !self.in_document {
+                self.skip_whitespaces();
+            }
+            if self.is_eof() {
+                return None;
+            }
+
+            let start = self.pos;
+            match self.read_token() {
+                Ok(Token::Sgml) => continue,
+                Ok(token) => {
+                    if matches!(token, Token::Tag(_)) {
+                        self.in_document = true;
+                    }
+                    return Some(Ok(token));
+                }
+                Err(TokenizeError::Malformed) => {
+                    self.malformed.push(start);
+                    continue;
+                }
+                Err(e) => {
+                    self.pos = self.source.len();
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::vec;
@@ -286,7 +368,7 @@ mod tests {
         let mut t = Tokenizer::new("");
         match t.tokenize() {
             Ok(tokens) => assert_eq!(tokens, vec![]),
-            Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
@@ -295,7 +377,7 @@ mod tests {
         let mut t = Tokenizer::new("<!DOCTYPE html>");
         match t.tokenize() {
             Ok(tokens) => assert_eq!(tokens, vec![]),
-            Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
@@ -303,7 +385,7 @@ mod tests {
     // fn test_tokenizer_tokenize_doctype_without_bang() {
     //     let mut t = Tokenizer::new("<DOCTYPE html>");
     //     match t.tokenize() {
-    //         Ok(tokens) => assert!(false, "Expected Err but got Ok: token = {:?}", tokens),
+    //         Ok(tokens) => panic!("Expected Err but got Ok: token = {:?}", tokens),
     //         Err(_) => assert!(true),
     //     }
     // }
@@ -320,7 +402,7 @@ mod tests {
                     attributes: AttributeMap::new(),
                 }),]
             ),
-            Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
@@ -336,7 +418,7 @@ mod tests {
                     attributes: AttributeMap::new(),
                 }),]
             ),
-            Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
@@ -359,7 +441,7 @@ mod tests {
                     }),
                 ]
             ),
-            Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
@@ -375,7 +457,7 @@ mod tests {
                     attributes: AttributeMap::new(),
                 }),]
             ),
-            Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
@@ -391,7 +473,7 @@ mod tests {
                     attributes: AttributeMap::new(),
                 }),]
             ),
-            Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
@@ -400,7 +482,7 @@ mod tests {
         let mut t = Tokenizer::new("</foobar/>");
         match t.tokenize() {
             Ok(tokens) => assert_eq!(tokens, vec![]),
-            Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
@@ -408,8 +490,8 @@ mod tests {
     fn test_tokenizer_tokenize_only_opening_bracket() {
         let mut t = Tokenizer::new("<");
         match t.tokenize() {
-            Ok(tokens) => assert!(false, "Expected Err but got Ok({:?})", tokens),
-            Err(e) => assert_eq!(e, TokenizeError::UnexpectedEOF),
+            Ok(tokens) => assert_eq!(tokens, vec![Token::Text("<".into())]),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
@@ -417,17 +499,17 @@ mod tests {
     // fn test_tokenizer_tokenize_only_closing_bracket() {
     //     let mut t = Tokenizer::new(">");
     //     match t.tokenize() {
-    //         Ok(tokens) => assert!(false, "Expected Err but got Ok({:?})", tokens),
+    //         Ok(tokens) => panic!("Expected Err but got Ok({:?})", tokens),
     //         Err(e) => assert_eq!(e, TokenizeError::UnexpectedChar('<', '>')),
     //     }
     // }
 
     #[test]
-    fn test_tokenizer_tokenize_missing_tag_name_is_ignored() {
+    fn test_tokenizer_tokenize_missing_tag_name_is_text() {
         let mut t = Tokenizer::new("<>");
         match t.tokenize() {
-            Ok(tokens) => assert_eq!(tokens, vec![]),
-            Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+            Ok(tokens) => assert_eq!(tokens, vec![Token::Text("<>".into())]),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
@@ -435,7 +517,7 @@ mod tests {
     // fn test_tokenizer_tokenize_missing_opening_bracket() {
     //     let mut t = Tokenizer::new("a>");
     //     match t.tokenize() {
-    //         Ok(tokens) => assert!(false, "Expected Err but got Ok({:?})", tokens),
+    //         Ok(tokens) => panic!("Expected Err but got Ok({:?})", tokens),
     //         Err(e) => assert_eq!(e, TokenizeError::UnexpectedClosingBracket),
     //     }
     // }
@@ -444,7 +526,7 @@ mod tests {
     fn test_tokenizer_tokenize_missing_closing_bracket() {
         let mut t = Tokenizer::new("<a");
         match t.tokenize() {
-            Ok(tokens) => assert!(false, "Expected Err but got Ok({:?})", tokens),
+            Ok(tokens) => panic!("Expected Err but got Ok({:?})", tokens),
             Err(e) => assert_eq!(e, TokenizeError::UnexpectedEOF),
         }
     }
@@ -453,8 +535,83 @@ mod tests {
     fn test_tokenizer_tokenize_text() {
         let mut t = Tokenizer::new("abcde");
         match t.tokenize() {
-            Ok(tokens) => assert_eq!(tokens, vec![Token::Text("abcde".to_string()),]),
-            Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+            Ok(tokens) => assert_eq!(tokens, vec![Token::Text("abcde".into()),]),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_tokenizer_iterate() {
+        let mut t = Tokenizer::new("<p>a<br><!-- c --></p><br");
+        assert_eq!(
+            t.next(),
+            Some(Ok(Token::Tag(Tag {
+                name: "p".to_string(),
+                kind: TagKind::Open,
+                attributes: AttributeMap::new(),
+            })))
+        );
+        assert_eq!(t.next(), Some(Ok(Token::Text("a".into()))));
+        assert_eq!(
+            t.next(),
+            Some(Ok(Token::Tag(Tag {
+                name: "br".to_string(),
+                kind: TagKind::Void,
+                attributes: AttributeMap::new(),
+            })))
+        );
+        assert_eq!(
+            t.next(),
+            Some(Ok(Token::Tag(Tag {
+                name: "p".to_string(),
+                kind: TagKind::Close,
+                attributes: AttributeMap::new(),
+            })))
+        );
+        assert_eq!(t.next(), Some(Err(TokenizeError::UnexpectedEOF)));
+        assert_eq!(t.next(), None);
+    }
+
+    #[test]
+    fn test_tokenizer_tokenize_text_borrows_source() {
+        let mut t = Tokenizer::new("<p>abc &amp; def</p>");
+        match t.tokenize() {
+            Ok(tokens) => match &tokens[1] {
+                Token::Text(Cow::Borrowed(content)) => assert_eq!(*content, "abc &amp; def"),
+                token => panic!("Expected borrowed text but got {:?}", token),
+            },
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_tokenizer_tokenize_multibyte() {
+        let mut t = Tokenizer::new("日本<p title=\"語\">\u{3000}é<Ä>ü</Ä>");
+        match t.tokenize() {
+            Ok(tokens) => assert_eq!(
+                tokens,
+                vec![
+                    Token::Text("日本".into()),
+                    Token::Tag(Tag {
+                        name: "p".to_string(),
+                        kind: TagKind::Open,
+                        attributes: AttributeMap::from([("title".to_string(), "語".to_string())]),
+                    }),
+                    Token::Text("\u{3000}é".into()),
+                    Token::Tag(Tag {
+                        name: "Ä".to_string(),
+                        kind: TagKind::Open,
+                        attributes: AttributeMap::new(),
+                    }),
+                    Token::Text("ü".into()),
+                    Token::Tag(Tag {
+                        name: "Ä".to_string(),
+                        kind: TagKind::Close,
+                        attributes: AttributeMap::new(),
+                    }),
+                ]
+            ),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
@@ -470,7 +627,7 @@ mod tests {
                     attributes: AttributeMap::from([("src".to_string(), "hello.png".to_string()),]),
                 })]
             ),
-            Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
@@ -489,7 +646,182 @@ mod tests {
                     ]),
                 })]
             ),
-            Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_tokenizer_tokenize_invalid_attribute_name() {
+        // The malformed tag is skipped instead of looping forever.
+        for source in ["<a#>", "<a href=\"x\" #>"] {
+            let mut t = Tokenizer::new(source);
+            match t.tokenize() {
+                Ok(tokens) => assert_eq!(tokens, vec![Token::Text("#>".into())]),
+                Err(e) => panic!("Expected Ok but got Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_tokenizer_tokenize_duplicate_attributes() {
+        let mut t = Tokenizer::new("<a href=\"x\" href=\"y\">");
+        match t.tokenize() {
+            Ok(tokens) => assert_eq!(
+                tokens,
+                vec![Token::Tag(Tag {
+                    name: "a".to_string(),
+                    kind: TagKind::Open,
+                    attributes: AttributeMap::from([("href".to_string(), "x".to_string())]),
+                })]
+            ),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
+        }
+        assert_eq!(
+            t.warnings(),
+            vec!["duplicate `href` attribute is ignored.".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_tokenizer_count_malformed_tokens() {
+        let mut t = Tokenizer::new("<p>a</p></>b</br><span/>c");
+        match t.tokenize() {
+            Ok(tokens) => assert_eq!(
+                tokens,
+                vec![
+                    Token::Tag(Tag {
+                        name: "p".to_string(),
+                        kind: TagKind::Open,
+                        attributes: AttributeMap::new(),
+                    }),
+                    Token::Text(Cow::from("a")),
+                    Token::Tag(Tag {
+                        name: "p".to_string(),
+                        kind: TagKind::Close,
+                        attributes: AttributeMap::new(),
+                    }),
+                    Token::Text(Cow::from("b")),
+                    Token::Text(Cow::from("c")),
+                ]
+            ),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
+        }
+        assert_eq!(t.malformed_count(), 3);
+        assert_eq!(t.malformed_positions(), &[8, 12, 17]);
+    }
+
+    #[test]
+    fn test_tokenizer_tokenize_attribute_with_spaced_equals() {
+        for source in ["<a href = \"x\">", "<a href\n=\t\"x\">"] {
+            let mut t = Tokenizer::new(source);
+            match t.tokenize() {
+                Ok(tokens) => assert_eq!(
+                    tokens,
+                    vec![Token::Tag(Tag {
+                        name: "a".to_string(),
+                        kind: TagKind::Open,
+                        attributes: AttributeMap::from([("href".to_string(), "x".to_string())]),
+                    })]
+                ),
+                Err(e) => panic!("Expected Ok but got Err({:?})", e),
+            }
+        }
+        let mut t = Tokenizer::new("<input disabled name=\"x\">");
+        match t.tokenize() {
+            Ok(tokens) => assert_eq!(
+                tokens,
+                vec![Token::Tag(Tag {
+                    name: "input".to_string(),
+                    kind: TagKind::Void,
+                    attributes: AttributeMap::from([
+                        ("disabled".to_string(), "disabled".to_string()),
+                        ("name".to_string(), "x".to_string()),
+                    ]),
+                })]
+            ),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_tokenizer_tokenize_attributes_with_colon_and_dot() {
+        let mut t = Tokenizer::new("<a v-bind:href=\"url\" data.foo=\"x.y\" xml:lang=\"en\">");
+        match t.tokenize() {
+            Ok(tokens) => assert_eq!(
+                tokens,
+                vec![Token::Tag(Tag {
+                    name: "a".to_string(),
+                    kind: TagKind::Open,
+                    attributes: AttributeMap::from([
+                        ("v-bind:href".to_string(), "url".to_string()),
+                        ("data.foo".to_string(), "x.y".to_string()),
+                        ("xml:lang".to_string(), "en".to_string()),
+                    ]),
+                })]
+            ),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_tokenizer_tokenize_mixed_case_attribute_value() {
+        let mut t = Tokenizer::new("<img SRC=\"Images/Cat.PNG\" Alt=\"A Cat\">");
+        match t.tokenize() {
+            Ok(tokens) => assert_eq!(
+                tokens,
+                vec![Token::Tag(Tag {
+                    name: "img".to_string(),
+                    kind: TagKind::Void,
+                    attributes: AttributeMap::from([
+                        ("src".to_string(), "Images/Cat.PNG".to_string()),
+                        ("alt".to_string(), "A Cat".to_string()),
+                    ]),
+                })]
+            ),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_tokenizer_tokenize_single_quoted_and_unquoted_attribute_values() {
+        let mut t = Tokenizer::new("<p class=MsoListParagraph style='mso-list:l0 level1 lfo1'>");
+        match t.tokenize() {
+            Ok(tokens) => assert_eq!(
+                tokens,
+                vec![Token::Tag(Tag {
+                    name: "p".to_string(),
+                    kind: TagKind::Open,
+                    attributes: AttributeMap::from([
+                        ("class".to_string(), "MsoListParagraph".to_string()),
+                        ("style".to_string(), "mso-list:l0 level1 lfo1".to_string()),
+                    ]),
+                })]
+            ),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_tokenizer_tokenize_less_than_sign_in_text() {
+        let mut t = Tokenizer::new("<p>1 < 2 <3</p>");
+        match t.tokenize() {
+            Ok(tokens) => assert_eq!(
+                tokens,
+                vec![
+                    Token::Tag(Tag {
+                        name: "p".to_string(),
+                        kind: TagKind::Open,
+                        attributes: AttributeMap::new(),
+                    }),
+                    Token::Text(Cow::Borrowed("1 < 2 <3")),
+                    Token::Tag(Tag {
+                        name: "p".to_string(),
+                        kind: TagKind::Close,
+                        attributes: AttributeMap::new(),
+                    }),
+                ]
+            ),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
@@ -508,13 +840,13 @@ mod tests {
                     )]),
                 })]
             ),
-            Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
     #[test]
     fn test_tokenizer_tokenize_void_tag_without_ending_slash() {
-        for tag in vec![
+        for tag in [
             "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param",
             "source", "track", "wbr",
         ] {
@@ -529,7 +861,7 @@ mod tests {
                         attributes: AttributeMap::new(),
                     })]
                 ),
-                Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+                Err(e) => panic!("Expected Ok but got Err({:?})", e),
             }
         }
     }
@@ -546,7 +878,7 @@ mod tests {
                     attributes: AttributeMap::new(),
                 })]
             ),
-            Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
@@ -562,17 +894,17 @@ mod tests {
                     attributes: AttributeMap::new(),
                 })]
             ),
-            Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
     #[test]
     fn test_tokenizer_tokenize_void_element_with_beginning_slash_is_ignored() {
-        for tag in vec!["</br>", "</br/>"] {
+        for tag in ["</br>", "</br/>"] {
             let mut t = Tokenizer::new(tag);
             match t.tokenize() {
                 Ok(tokens) => assert_eq!(tokens, vec![]),
-                Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+                Err(e) => panic!("Expected Ok but got Err({:?})", e),
             }
         }
     }
@@ -589,7 +921,7 @@ mod tests {
                     attributes: AttributeMap::new(),
                 })]
             ),
-            Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
@@ -605,17 +937,71 @@ mod tests {
                     attributes: AttributeMap::new(),
                 })]
             ),
-            Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
+        }
+    }
+
+    #[test]
+    fn test_tokenizer_tokenize_custom_and_namespaced_elements() {
+        for name in ["my-widget", "foo:bar"] {
+            let source = format!("<{} id=\"x\"></{}>", name, name);
+            let mut t = Tokenizer::new(&source);
+            match t.tokenize() {
+                Ok(tokens) => assert_eq!(
+                    tokens,
+                    vec![
+                        Token::Tag(Tag {
+                            name: name.to_string(),
+                            kind: TagKind::Open,
+                            attributes: AttributeMap::from([("id".to_string(), "x".to_string())]),
+                        }),
+                        Token::Tag(Tag {
+                            name: name.to_string(),
+                            kind: TagKind::Close,
+                            attributes: AttributeMap::new(),
+                        }),
+                    ]
+                ),
+                Err(e) => panic!("Expected Ok but got Err({:?})", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_tokenizer_tokenize_svg() {
+        let mut t = Tokenizer::new("<svg class=\"Icon\"><path d=\"M0 0L1 1\"/></svg><a/>");
+        match t.tokenize() {
+            Ok(tokens) => assert_eq!(
+                tokens,
+                vec![
+                    Token::Tag(Tag {
+                        name: "svg".to_string(),
+                        kind: TagKind::Open,
+                        attributes: AttributeMap::from([("class".to_string(), "Icon".to_string())]),
+                    }),
+                    Token::Tag(Tag {
+                        name: "path".to_string(),
+                        kind: TagKind::Void,
+                        attributes: AttributeMap::from([("d".to_string(), "M0 0L1 1".to_string())]),
+                    }),
+                    Token::Tag(Tag {
+                        name: "svg".to_string(),
+                        kind: TagKind::Close,
+                        attributes: AttributeMap::new(),
+                    }),
+                ]
+            ),
+            Err(e) => panic!("Expected Ok but got Err({:?})", e),
         }
     }
 
     #[test]
     fn test_tokenizer_tokenize_non_void_element_with_ending_slash_is_ignored() {
-        for tag in vec!["<a/>", "</a/>"] {
+        for tag in ["<a/>", "</a/>"] {
             let mut t = Tokenizer::new(tag);
             match t.tokenize() {
                 Ok(tokens) => assert_eq!(tokens, vec![]),
-                Err(e) => assert!(false, "Expected Ok but got Err({:?})", e),
+                Err(e) => panic!("Expected Ok but got Err({:?})", e),
             }
         }
     }
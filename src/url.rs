@@ -0,0 +1,150 @@
+// Resolves a URL against a base URL like `https://example.com/docs/index.html`.
+//
+// Absolute URLs are kept, `//host/x` takes the scheme of the base, `/x` is
+// joined to the origin, `?q` replaces the query and anything else is joined
+// to the directory of the base path with `.` and `..` segments removed.
+// Fragment-only URLs like `#sec` are kept so that they still point into the
+// converted document.
+pub fn resolve_url(base: &str, url: &str) -> String {
+    if url.is_empty() || url.starts_with('#') || has_scheme(url) {
+        return url.to_string();
+    }
+
+    let (scheme, rest) = match base.split_once("://") {
+        Some((scheme, rest)) => (Some(scheme), rest),
+        None => (None, base),
+    };
+    if let Some(authority_and_path) = url.strip_prefix("//") {
+        return match scheme {
+            Some(scheme) => format!("{}://{}", scheme, authority_and_path),
+            None => url.to_string(),
+        };
+    }
+
+    // The base is split into an origin like `https://host` and a path without
+    // query and fragment.
+    let (authority, path_and_more) = match scheme {
+        Some(_) => match rest.find(['/', '?', '#']) {
+            Some(pos) => rest.split_at(pos),
+            None => (rest, ""),
+        },
+        None => ("", rest),
+    };
+    let origin = match scheme {
+        Some(scheme) => format!("{}://{}", scheme, authority),
+        None => String::new(),
+    };
+    let path = match path_and_more.find(['?', '#']) {
+        Some(pos) => &path_and_more[..pos],
+        None => path_and_more,
+    };
+
+    if url.starts_with('?') {
+        let path = if path.is_empty() && scheme.is_some() {
+            "/"
+        } else {
+            path
+        };
+        return format!("{}{}{}", origin, path, url);
+    }
+
+    let joined = if url.starts_with('/') {
+        url.to_string()
+    } else {
+        let directory = match path.rfind('/') {
+            Some(pos) => &path[..(pos + 1)],
+            None if scheme.is_some() => "/",
+            None => "",
+        };
+        format!("{}{}", directory, url)
+    };
+    format!("{}{}", origin, remove_dot_segments(&joined))
+}
+
+pub(crate) fn has_scheme(url: &str) -> bool {
+    match url.find([':', '/', '?', '#']) {
+        Some(pos) if url[pos..].starts_with(':') => {
+            let scheme = &url[..pos];
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+        }
+        _ => false,
+    }
+}
+
+// `a/./b/../c` becomes `a/c`. The query and fragment are left as they are.
+fn remove_dot_segments(url: &str) -> String {
+    let (path, suffix) = match url.find(['?', '#']) {
+        Some(pos) => url.split_at(pos),
+        None => (url, ""),
+    };
+
+    let mut segments: Vec<&str> = Vec::new();
+    let parts: Vec<&str> = path.split('/').collect();
+    for (i, segment) in parts.iter().enumerate() {
+        let last = i == parts.len() - 1;
+        match *segment {
+            "." => {
+                if last {
+                    segments.push("");
+                }
+            }
+            ".." => {
+                if segments.len() > 1 || segments.first().is_some_and(|s| !s.is_empty()) {
+                    segments.pop();
+                }
+                if last {
+                    segments.push("");
+                }
+            }
+            _ => segments.push(segment),
+        }
+    }
+
+    format!("{}{}", segments.join("/"), suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_url() {
+        let base = "https://example.com/docs/guide/index.html?lang=en#top";
+        for (url, expected) in [
+            ("https://other.com/x", "https://other.com/x"),
+            ("mailto:a@example.com", "mailto:a@example.com"),
+            ("//cdn.example.com/a.png", "https://cdn.example.com/a.png"),
+            ("/about", "https://example.com/about"),
+            ("intro.html", "https://example.com/docs/guide/intro.html"),
+            ("./img/a.png", "https://example.com/docs/guide/img/a.png"),
+            ("../api/", "https://example.com/docs/api/"),
+            ("../../../x", "https://example.com/x"),
+            ("..", "https://example.com/docs/"),
+            (
+                "?lang=ja",
+                "https://example.com/docs/guide/index.html?lang=ja",
+            ),
+            ("#sec", "#sec"),
+            ("", ""),
+        ] {
+            assert_eq!(resolve_url(base, url), expected, "url = {:?}", url);
+        }
+    }
+
+    #[test]
+    fn test_resolve_url_with_bare_base() {
+        assert_eq!(
+            resolve_url("https://example.com", "a.html"),
+            "https://example.com/a.html"
+        );
+        assert_eq!(
+            resolve_url("https://example.com", "?q"),
+            "https://example.com/?q"
+        );
+        assert_eq!(resolve_url("/docs/", "a.html"), "/docs/a.html");
+        assert_eq!(resolve_url("/docs/", "//host/x"), "//host/x");
+    }
+}
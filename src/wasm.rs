@@ -0,0 +1,7 @@
+use wasm_bindgen::prelude::*;
+
+/// Converts like `convert`, rejecting with the error message in JavaScript.
+#[wasm_bindgen]
+pub fn convert_js(source: &str) -> Result<String, JsValue> {
+    crate::convert(source).map_err(|e| JsValue::from_str(&e.to_string()))
+}
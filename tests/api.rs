@@ -0,0 +1,97 @@
+use html2md::render::Renderer;
+use html2md::{parse_html, render_node, restruct, AttributeMap, Element, Node, Options};
+
+#[test]
+fn test_parse_mutate_and_render() {
+    let source = "<html><head></head><body><p>hello</p><ul><li>world</li></ul></body></html>";
+    let options = Options::default();
+
+    let mut node = match parse_html(source) {
+        Ok(node) => node,
+        Err(e) => panic!("Unexpected Err({:?})", e),
+    };
+    node.walk_mut(&mut |n| {
+        if let Node::Text(content) = n {
+            if content == "world" {
+                *content = "everyone".into();
+            }
+        }
+    });
+
+    let node = restruct(&node, &options);
+    match render_node(&node, &options) {
+        Ok(result) => assert_eq!(result, "hello\n\n- everyone\n"),
+        Err(e) => panic!("Unexpected Err({:?})", e),
+    }
+}
+
+#[test]
+fn test_walk_collecting_hrefs() {
+    let source = "<html><head></head><body><p><a href=\"https://example.com\">hello</a></p><ul><li><a href=\"/world\">world</a></li><li><a name=\"foo\">foo</a></li></ul></body></html>";
+
+    let node = match parse_html(source) {
+        Ok(node) => node,
+        Err(e) => panic!("Unexpected Err({:?})", e),
+    };
+
+    let mut hrefs = Vec::new();
+    node.walk(&mut |n| {
+        if let Node::Element(element) = n {
+            if element.tag_name == "a" {
+                if let Some(href) = element.attributes.get("href") {
+                    hrefs.push(href.clone());
+                }
+            }
+        }
+    });
+    assert_eq!(hrefs, vec!["https://example.com", "/world"]);
+}
+
+#[test]
+fn test_render_constructed_attributes_in_any_case() {
+    let link = Element::new_with_children(
+        "a",
+        &AttributeMap::from([("HREF".to_string(), "https://example.com".to_string())]),
+        vec![Node::Text("hello".into())],
+    );
+    let body = Element::new_with_children("body", &AttributeMap::new(), vec![Node::Element(link)]);
+    let node = Node::Element(Element::new_with_children(
+        "html",
+        &AttributeMap::new(),
+        vec![Node::Element(body)],
+    ));
+
+    let options = Options::default();
+    let node = restruct(&node, &options);
+    match render_node(&node, &options) {
+        Ok(result) => assert_eq!(result, "[hello](https://example.com)\n"),
+        Err(e) => panic!("Unexpected Err({:?})", e),
+    }
+}
+
+#[test]
+fn test_render_with_custom_handler() {
+    let source = "<html><head></head><body><p>hello</p><callout type=\"warning\">be <em>careful</em></callout><p>bye</p></body></html>";
+    let options = Options::default();
+
+    let node = match parse_html(source) {
+        Ok(node) => node,
+        Err(e) => panic!("Unexpected Err({:?})", e),
+    };
+    let node = restruct(&node, &options);
+    let mut renderer = Renderer::new(&node, &options);
+    renderer.set_handler("callout", |element, renderer| {
+        let kind = element.attributes.get("type").cloned().unwrap_or_default();
+        let content = renderer.render_children(element)?;
+        Ok(format!(
+            "\n\n> [!{}]\n> {}\n\n",
+            kind.to_uppercase(),
+            content
+        ))
+    });
+    match renderer.render() {
+        Ok(result) => assert_eq!(result, "hello\n\n> [!WARNING]\n> be _careful_\n\nbye\n"),
+        Err(e) => panic!("Unexpected Err({:?})", e),
+    }
+    assert!(renderer.warnings().is_empty());
+}
@@ -0,0 +1,28 @@
+use std::fs;
+
+use html2md::convert;
+
+// Converting the Markdown again as the text of a document must not change it.
+#[test]
+fn test_convert_is_idempotent() {
+    let mut paths: Vec<_> = fs::read_dir("tests/fixtures/roundtrip")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "html"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty());
+
+    for path in paths {
+        let source = fs::read_to_string(&path).unwrap();
+        let first = match convert(&source) {
+            Ok(result) => result,
+            Err(e) => panic!("Unexpected Err({:?}) for {}", e, path.display()),
+        };
+        let wrapped = format!("<html><head></head><body>{}</body></html>", first);
+        match convert(&wrapped) {
+            Ok(second) => assert_eq!(second, first, "{}", path.display()),
+            Err(e) => panic!("Unexpected Err({:?}) for {}", e, path.display()),
+        }
+    }
+}